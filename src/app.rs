@@ -1,83 +1,267 @@
 use crossterm::event::KeyEvent;
 use crate::system::SystemInfo;
-use crate::reactor::Reactor;
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use std::thread;
+use crate::metrics::{MetricsSource, Playback};
+use crate::model::ModelKind;
+use crate::control::{PidController, PidParameter, PidTuning};
+use crate::scenario::{self, Scenario, ScenarioPlayer, DEFAULT_SCENARIO_DIR};
+use crate::stress::{StressConfig, StressTest};
+use crate::game::{default_high_score_path, Difficulty, Game, HighScores, OperatorControls, ROD_BANKS};
+use crate::thermal::PumpDrive;
+use crate::sampler::{Sampler, SamplerConfig};
+use crate::recording::{Recorder, RecordingHeader};
+use crate::reactor::{MeltdownCriteria, Reactor, ReactorInput, ReactorPhase, TemperatureSource, FIXED_TIMESTEP};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+const REPLAY_SEEK_SECONDS: f32 = 5.0;
 #[derive(PartialEq, Eq)]
 pub enum AppState {
     Running,
     Paused,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub sampler: SamplerConfig,
+    // Reactor RNG seed; a random one is picked when unset
+    pub seed: Option<u64>,
+    pub temperature_source: TemperatureSource,
+    pub meltdown: MeltdownCriteria,
+    pub model: ModelKind,
+    pub pump_drive: PumpDrive,
+    // Start in operator mode at this difficulty
+    pub game: Option<Difficulty>,
+    // Where game mode keeps its high scores; `default_high_score_path` if unset
+    pub high_score_path: Option<PathBuf>,
+    // Start with the PID controller driving the rods
+    pub pid: bool,
+    pub pid_tuning: PidTuning,
+    // Where the scenario menu finds scenario files; DEFAULT_SCENARIO_DIR if unset
+    pub scenario_dir: Option<PathBuf>,
+    // Play this load scenario from the start
+    pub scenario: Option<Scenario>,
+    // What 's' starts, and whether it is running from the start
+    pub stress: StressConfig,
+    pub stress_on_start: bool,
+}
+
+// Everything the operator can do to the simulation. A recording logs each one
+// with the step it was taken before, so a replay takes it at the same point.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Scram,
+    Restart,
+    Refuel,
+    NextModel,
+    ToggleTemperatureSource,
+    ToggleGame,
+    ToggleController,
+    // Operator mode: which rod bank moves (None for all of them), and by how
+    // many steps the rods and pumps move
+    SelectBank(Option<usize>),
+    MoveRods(f32),
+    AdjustPumps(f32),
+    Tune(PidParameter, f32),
+    // Play a scenario, or go back to the real load only
+    PlayScenario(Option<Scenario>),
+    NextBlend,
+}
+
 pub struct App {
     pub state: AppState,
     pub system_info: SystemInfo,
+    // The last snapshot from the source, before any scenario was blended in
+    measured_info: SystemInfo,
+    source: Box<dyn MetricsSource>,
     pub reactor: Reactor,
     pub show_help: bool,
     pub reactor_status: String,
-    stress_thread: Option<StressThread>,
-    simulation_active: bool,
-    simulation_value: f32,
-    simulation_direction: f32,
-}
-
-struct StressThread {
-    handle: thread::JoinHandle<()>,
-    stop_flag: Arc<AtomicBool>,
+    // Operator mode, when it is being played
+    pub game: Option<Game>,
+    difficulty: Difficulty,
+    high_score_path: PathBuf,
+    // Kept while the controller is off, so switching back resumes the tuning
+    pid_tuning: PidTuning,
+    pub pid_parameter: PidParameter,
+    // Real load on this machine, while the stress test runs
+    pub stress: Option<StressTest>,
+    stress_config: StressConfig,
+    // Scripted load, and the scenarios the menu offers
+    pub scenario: Option<ScenarioPlayer>,
+    pub scenarios: Vec<Scenario>,
+    // Scenario files that couldn't be loaded
+    pub scenario_errors: Vec<String>,
+    // Highlighted menu entry while the scenario menu is open; 0 is real load only
+    pub scenario_menu: Option<usize>,
+    // Simulation steps run so far; recordings are timed by them
+    steps: u64,
+    recorder: Option<Recorder>,
 }
 
 impl App {
     pub fn new() -> Self {
-        App {
+        Self::with_config(AppConfig::default())
+    }
+
+    pub fn with_config(config: AppConfig) -> Self {
+        let source = Box::new(Sampler::system(config.sampler.clone()));
+        Self::with_source(source, config)
+    }
+
+    // Drive the reactor from any metrics source instead of the host
+    pub fn with_source(source: Box<dyn MetricsSource>, config: AppConfig) -> Self {
+        let mut reactor = match config.seed {
+            Some(seed) => Reactor::with_seed(seed),
+            None => Reactor::new(),
+        };
+        reactor.temperature_source = config.temperature_source;
+        reactor.meltdown = config.meltdown;
+        reactor.set_model(config.model);
+        reactor.coolant.drive = config.pump_drive;
+        let scenario_dir = config.scenario_dir.unwrap_or_else(|| PathBuf::from(DEFAULT_SCENARIO_DIR));
+        let (scenarios, scenario_errors) = scenario::library(scenario_dir);
+
+        let mut app = App {
             state: AppState::Running,
-            system_info: SystemInfo::new(),
-            reactor: Reactor::new(),
+            system_info: SystemInfo::default(),
+            measured_info: SystemInfo::default(),
+            source,
+            reactor,
             show_help: false,
             reactor_status: "Normal Operation".to_string(),
-            stress_thread: None,
-            simulation_active: false,
-            simulation_value: 40.0,
-            simulation_direction: 1.0,
-        }
-    }
-
-    pub fn update(&mut self) {
-        // Only update system info if the app is running
-        if self.state == AppState::Running {
-            self.system_info.update();
-            
-            // If simulation is active, override CPU usage with a smooth oscillating value
-            if self.simulation_active {
-                // Update simulation value to create a smooth wave pattern
-                self.simulation_value += self.simulation_direction * 0.5;
-                
-                // Reverse direction at boundaries for oscillation
-                if self.simulation_value > 95.0 {
-                    self.simulation_value = 95.0;
-                    self.simulation_direction = -1.0;
-                } else if self.simulation_value < 20.0 {
-                    self.simulation_value = 20.0;
-                    self.simulation_direction = 1.0;
-                }
-                
-                // Ensure CPU usage is always within valid range
-                self.system_info.cpu_usage = self.simulation_value.max(0.0).min(100.0);
+            game: None,
+            difficulty: config.game.unwrap_or_default(),
+            high_score_path: config.high_score_path.unwrap_or_else(default_high_score_path),
+            pid_tuning: config.pid_tuning,
+            pid_parameter: PidParameter::default(),
+            stress: config.stress_on_start.then(|| StressTest::start(config.stress)),
+            stress_config: config.stress,
+            scenario: config.scenario.map(ScenarioPlayer::new),
+            scenarios,
+            scenario_errors,
+            scenario_menu: None,
+            steps: 0,
+            recorder: None,
+        };
+        if config.game.is_some() {
+            app.toggle_game();
+        } else if config.pid {
+            app.toggle_controller();
+        }
+        app
+    }
+
+    // Like `with_source`, recording the session to `path` as it runs. The
+    // recording starts with the config, so the seed is picked here if unset.
+    pub fn with_recording(source: Box<dyn MetricsSource>, mut config: AppConfig, path: impl AsRef<Path>) -> Result<Self> {
+        config.seed.get_or_insert_with(rand::random);
+        let recorder = Recorder::create(path, RecordingHeader { config: config.clone() })?;
+        let mut app = Self::with_source(source, config);
+        app.recorder = Some(recorder);
+        Ok(app)
+    }
+
+    // Advance by `dt` of real time since the previous update
+    pub fn update(&mut self, dt: Duration) {
+        // A stress test with a time limit ends by itself
+        if self.stress.as_ref().is_some_and(StressTest::is_expired)
+            && let Some(stress) = self.stress.take()
+        {
+            stress.stop();
+        }
+        
+        // Nothing is sampled while paused, so a recording skips the pause
+        if self.state == AppState::Paused {
+            return;
+        }
+        
+        // A replay runs at its own speed, and stands still while it is paused
+        let speed = match self.source.playback() {
+            Some(playback) if playback.is_paused() => 0.0,
+            Some(playback) => playback.speed(),
+            None => 1.0,
+        };
+        for _ in 0..self.reactor.due_steps(dt.as_secs_f32(), speed) {
+            self.step();
+        }
+        
+        // Update reactor status based on system load
+        self.update_reactor_status();
+    }
+    
+    // Run one fixed step. Live and replayed sessions both come through here,
+    // so every step sees the snapshot and the operator actions it saw when
+    // it was recorded.
+    fn step(&mut self) {
+        let actions = self.source.playback().map(Playback::actions).unwrap_or_default();
+        for action in actions {
+            self.apply(action);
+        }
+        
+        self.source.advance(Duration::from_secs_f32(FIXED_TIMESTEP));
+        if let Some(info) = self.source.sample() {
+            if let Some(recorder) = &mut self.recorder {
+                recorder.frame(self.steps, &info);
             }
-            
-            self.reactor.update(self.system_info.cpu_usage.max(0.0).min(100.0));
-            
-            // Update reactor status based on system load
-            self.update_reactor_status();
+            self.measured_info = info;
+        }
+        
+        // Blend any scenario into the real load, starting afresh from the
+        // last snapshot each time so the blend doesn't compound
+        self.system_info = self.measured_info.clone();
+        if let Some(player) = &mut self.scenario {
+            player.advance(FIXED_TIMESTEP);
+            player.apply(&mut self.system_info);
+            if player.is_finished() {
+                self.scenario = None;
+            }
+        }
+        
+        let input = reactor_input(&self.system_info);
+        self.reactor.step(&input);
+        // The game scores simulated time, so a stalled frame earns no more
+        // than the reactor moved on
+        if let Some(game) = &mut self.game {
+            game.update(&self.reactor, FIXED_TIMESTEP);
+        }
+        if let Some(playback) = self.source.playback_mut() {
+            playback.step();
+        }
+        self.steps += 1;
+    }
+    
+    // Simulation steps run since the start
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+    
+    // Transport state when replaying a recording
+    pub fn playback(&self) -> Option<&dyn Playback> {
+        self.source.playback()
+    }
+
+    pub fn source_status(&self) -> Option<String> {
+        match &self.recorder {
+            Some(recorder) => Some(recorder.status()),
+            None => self.source.status(),
         }
     }
 
     fn update_reactor_status(&mut self) {
-        // If the reactor is exploding, set critical status
-        if self.reactor.is_exploding {
-            self.reactor_status = "CRITICAL - MELTDOWN IMMINENT!".to_string();
-            return;
+        match self.reactor.phase {
+            // If the reactor is exploding, set critical status
+            ReactorPhase::Meltdown => {
+                self.reactor_status = "CRITICAL - MELTDOWN IMMINENT!".to_string();
+                return;
+            }
+            ReactorPhase::Cooldown => {
+                self.reactor_status = "Shutdown - SCRAM, Core Cooling Down".to_string();
+                return;
+            }
+            ReactorPhase::Operating => {}
         }
 
         let stability = self.reactor.stability();
@@ -92,6 +276,10 @@ impl App {
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.scenario_menu.is_some() {
+            self.handle_menu_key(key.code);
+            return;
+        }
         match key.code {
             crossterm::event::KeyCode::Char('p') => {
                 self.toggle_pause();
@@ -102,10 +290,180 @@ impl App {
             crossterm::event::KeyCode::Char('s') => {
                 self.toggle_stress_test();
             }
+            crossterm::event::KeyCode::Char('t') => {
+                self.act(Action::ToggleTemperatureSource);
+            }
+            crossterm::event::KeyCode::Char('m') => {
+                self.act(Action::NextModel);
+            }
+            crossterm::event::KeyCode::Char('x') => {
+                self.act(Action::Scram);
+            }
+            crossterm::event::KeyCode::Char('r') => {
+                self.act(Action::Restart);
+            }
+            crossterm::event::KeyCode::Char('f') => {
+                self.act(Action::Refuel);
+            }
+            crossterm::event::KeyCode::Char('g') => {
+                self.act(Action::ToggleGame);
+            }
+            crossterm::event::KeyCode::Char('c') => {
+                self.act(Action::ToggleController);
+            }
+            crossterm::event::KeyCode::Char('l') => {
+                // Open on whatever is playing
+                let playing = self.scenario.as_ref()
+                    .and_then(|player| self.scenarios.iter().position(|s| *s == player.scenario));
+                self.scenario_menu = Some(playing.map_or(0, |index| index + 1));
+            }
+            crossterm::event::KeyCode::Char('b') => {
+                self.act(Action::NextBlend);
+            }
+            code => {
+                if !self.handle_operator_key(code) && !self.handle_controller_key(code) {
+                    self.handle_playback_key(code);
+                }
+            }
+        }
+    }
+    
+    // Rod banks and pumps in operator mode; returns whether the key was used
+    fn handle_operator_key(&mut self, code: crossterm::event::KeyCode) -> bool {
+        let banks = self.reactor.rods.len().min(ROD_BANKS);
+        if self.reactor.operator.is_none() {
+            return false;
+        }
+        let action = match code {
+            crossterm::event::KeyCode::Char('0') => Action::SelectBank(None),
+            crossterm::event::KeyCode::Char(digit @ '1'..='9') => {
+                let bank = digit as usize - '1' as usize;
+                if bank >= banks {
+                    return true;
+                }
+                Action::SelectBank(Some(bank))
+            }
+            crossterm::event::KeyCode::Up => Action::MoveRods(1.0),
+            crossterm::event::KeyCode::Down => Action::MoveRods(-1.0),
+            crossterm::event::KeyCode::Char(']') => Action::AdjustPumps(1.0),
+            crossterm::event::KeyCode::Char('[') => Action::AdjustPumps(-1.0),
+            _ => return false,
+        };
+        self.act(action);
+        true
+    }
+
+    // The scenario menu: real load only, then every scenario
+    fn handle_menu_key(&mut self, code: crossterm::event::KeyCode) {
+        let Some(selected) = self.scenario_menu else {
+            return;
+        };
+        let entries = self.scenarios.len() + 1;
+        match code {
+            crossterm::event::KeyCode::Up => self.scenario_menu = Some((selected + entries - 1) % entries),
+            crossterm::event::KeyCode::Down => self.scenario_menu = Some((selected + 1) % entries),
+            crossterm::event::KeyCode::Enter => {
+                let scenario = selected.checked_sub(1).map(|index| self.scenarios[index].clone());
+                self.act(Action::PlayScenario(scenario));
+                self.scenario_menu = None;
+            }
+            crossterm::event::KeyCode::Char('b') => self.act(Action::NextBlend),
+            crossterm::event::KeyCode::Esc | crossterm::event::KeyCode::Char('l') => self.scenario_menu = None,
+            _ => {}
+        }
+    }
+    
+    // Controller tuning: pick a parameter and nudge it; returns whether the key was used
+    fn handle_controller_key(&mut self, code: crossterm::event::KeyCode) -> bool {
+        if self.reactor.controller.is_none() {
+            return false;
+        }
+        match code {
+            crossterm::event::KeyCode::Tab => self.pid_parameter = self.pid_parameter.next(),
+            crossterm::event::KeyCode::Char('.') => self.act(Action::Tune(self.pid_parameter, 1.0)),
+            crossterm::event::KeyCode::Char(',') => self.act(Action::Tune(self.pid_parameter, -1.0)),
+            _ => return false,
+        }
+        true
+    }
+    
+    fn handle_playback_key(&mut self, code: crossterm::event::KeyCode) {
+        let Some(playback) = self.source.playback_mut() else {
+            return;
+        };
+        match code {
+            crossterm::event::KeyCode::Char(' ') => playback.toggle_pause(),
+            crossterm::event::KeyCode::Left => playback.seek_by(-REPLAY_SEEK_SECONDS),
+            crossterm::event::KeyCode::Right => playback.seek_by(REPLAY_SEEK_SECONDS),
+            crossterm::event::KeyCode::Home => playback.seek(Duration::ZERO),
+            crossterm::event::KeyCode::Char('+') | crossterm::event::KeyCode::Char('=') => {
+                let speed = playback.speed() * 2.0;
+                playback.set_speed(speed);
+            }
+            crossterm::event::KeyCode::Char('-') => {
+                let speed = playback.speed() / 2.0;
+                playback.set_speed(speed);
+            }
             _ => {}
         }
     }
 
+    // Take an action from the keyboard. A replay takes the recorded ones
+    // instead, so keys that would change the simulation do nothing there.
+    fn act(&mut self, action: Action) {
+        if self.source.playback().is_some() {
+            return;
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.action(self.steps, &action);
+        }
+        self.apply(action);
+    }
+    
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::Scram => {
+                self.reactor.scram();
+                self.update_reactor_status();
+            }
+            Action::Restart => {
+                self.reactor.restart();
+                self.update_reactor_status();
+            }
+            Action::Refuel => self.reactor.refuel(),
+            Action::NextModel => self.reactor.set_model(self.reactor.model_kind().next()),
+            Action::ToggleTemperatureSource => self.toggle_temperature_source(),
+            Action::ToggleGame => self.toggle_game(),
+            Action::ToggleController => self.toggle_controller(),
+            Action::SelectBank(bank) => {
+                if let Some(operator) = &mut self.reactor.operator {
+                    operator.selected = bank;
+                }
+            }
+            Action::MoveRods(steps) => {
+                if let Some(operator) = &mut self.reactor.operator {
+                    operator.move_rods(steps);
+                }
+            }
+            Action::AdjustPumps(steps) => {
+                if let Some(operator) = &mut self.reactor.operator {
+                    operator.adjust_pumps(steps);
+                }
+            }
+            Action::Tune(parameter, steps) => {
+                if let Some(controller) = &mut self.reactor.controller {
+                    parameter.adjust(&mut controller.tuning, steps);
+                }
+            }
+            Action::PlayScenario(scenario) => self.scenario = scenario.map(ScenarioPlayer::new),
+            Action::NextBlend => {
+                if let Some(player) = &mut self.scenario {
+                    player.blend = player.blend.next();
+                }
+            }
+        }
+    }
+
     fn toggle_pause(&mut self) {
         self.state = match self.state {
             AppState::Running => AppState::Paused,
@@ -113,53 +471,95 @@ impl App {
         };
     }
     
-    fn toggle_stress_test(&mut self) {
-        if let Some(stress_thread) = &self.stress_thread {
-            // Stop the stress test
-            stress_thread.stop_flag.store(true, Ordering::SeqCst);
-            self.stress_thread = None;
-            self.simulation_active = true; // Keep showing high usage in UI
+    // Hand the rods and pumps to the player, or back to the load. Leaving
+    // operator mode enters the game in progress into the high scores.
+    fn toggle_game(&mut self) {
+        if let Some(mut game) = self.game.take() {
+            game.finish();
+            self.reactor.operator = None;
         } else {
-            // Start the stress test or toggle simulation
-            if self.simulation_active {
-                self.simulation_active = false;
-            } else {
-                self.start_stress_test();
+            if self.reactor.controller.is_some() {
+                self.toggle_controller();
             }
+            let mut game = Game::new(self.difficulty, &self.high_score_path, &self.reactor);
+            // A replayed game was scored when it was played
+            if self.source.playback().is_some() {
+                game.high_scores = HighScores::in_memory(&self.high_score_path);
+            }
+            self.game = Some(game);
+            self.reactor.operator = Some(OperatorControls::new(
+                self.reactor.rod_position,
+                self.difficulty.load_gain(),
+            ));
         }
     }
     
-    fn start_stress_test(&mut self) {
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        let thread_stop_flag = stop_flag.clone();
-        
-        let handle = thread::spawn(move || {
-            // Create some CPU load by doing meaningless calculations
-            while !thread_stop_flag.load(Ordering::SeqCst) {
-                // Do some meaningless work to generate CPU load
-                for _ in 0..10_000_000 {
-                    let _result = 1 + 1;
-                }
-                
-                // Short pause to prevent 100% CPU usage
-                thread::sleep(Duration::from_millis(1));
+    // Put the rods under PID control to hold the core temperature, or hand
+    // them back to the load
+    fn toggle_controller(&mut self) {
+        if let Some(controller) = self.reactor.controller.take() {
+            self.pid_tuning = controller.tuning;
+        } else {
+            if self.game.is_some() {
+                self.toggle_game();
             }
-        });
-        
-        self.stress_thread = Some(StressThread {
-            handle,
-            stop_flag,
-        });
+            self.reactor.controller = Some(PidController::new(self.pid_tuning, self.reactor.rod_position));
+        }
+    }
+    
+    fn toggle_temperature_source(&mut self) {
+        self.reactor.temperature_source = match self.reactor.temperature_source {
+            TemperatureSource::Model => TemperatureSource::Measured,
+            TemperatureSource::Measured => TemperatureSource::Model,
+        };
+    }
+    
+    fn toggle_stress_test(&mut self) {
+        match self.stress.take() {
+            Some(stress) => stress.stop(),
+            None => self.stress = Some(StressTest::start(self.stress_config)),
+        }
+    }
+}
+
+fn reactor_input(info: &SystemInfo) -> ReactorInput {
+    ReactorInput {
+        cpu_load: info.cpu_usage,
+        core_loads: info.core_usage.clone(),
+        measured_temperature: info.cpu_temp,
+        memory_usage: info.memory_usage,
+        disk_io_rate: info.disk_read_rate + info.disk_write_rate,
+        context_switch_rate: info.context_switch_rate,
+        interrupt_rate: info.interrupt_rate,
+        page_fault_rate: info.page_fault_rate,
+        major_fault_rate: info.major_fault_rate,
+        swap_rate: info.swap_in_rate + info.swap_out_rate,
+        load_average: info.load_average[0],
+        network_rate: info.network_throughput(),
+        disk_saturation: info.disk_saturation(),
+        filesystem_usage: info.filesystem_usage(),
+        stall: info.pressure.stall(),
+        oom_kills: info.cgroup.as_ref().map(|cgroup| cgroup.oom_kills),
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Drop for App {
     fn drop(&mut self) {
-        // Make sure to terminate the stress test thread when the app exits
-        if let Some(stress_thread) = &self.stress_thread {
-            stress_thread.stop_flag.store(true, Ordering::SeqCst);
-            // We intentionally don't join the thread here to avoid blocking
-            // on app exit if the thread is somehow stuck
+        // Quitting mid-game still counts
+        if let Some(game) = &mut self.game {
+            game.finish();
+        }
+        
+        // Don't leave stress workers running behind the app; they check for
+        // the stop between short chunks of work, so this is quick
+        if let Some(stress) = self.stress.take() {
+            stress.stop();
         }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,169 @@
+use crate::procfs::{field, ProcFs};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Container-aware monitoring. Inside a container the host's CPU and memory
+// figures say little about the container itself; its cgroup (v2 only) says
+// what it actually uses, and what it is allowed.
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+// Counters and limits read from a cgroup's interface files at one moment.
+// The root cgroup has no limits, and no memory.current either.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CgroupReading {
+    // CPU time used, and spent throttled, since the cgroup was created
+    pub cpu_usage_usec: u64,
+    pub throttled_usec: u64,
+    // CPUs' worth of time allowed by cpu.max; None when unlimited
+    pub cpu_limit: Option<f32>,
+    pub memory_current: Option<u64>,
+    // None when unlimited
+    pub memory_max: Option<u64>,
+    // Processes killed by the OOM killer, this cgroup and below
+    pub oom_kills: u64,
+    // Summed over every device in io.stat
+    pub io_read_bytes: u64,
+    pub io_written_bytes: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CgroupFs {
+    root: PathBuf,
+    // The cgroup, relative to the root, e.g. "/system.slice/docker-1234.scope"
+    path: PathBuf,
+}
+
+impl CgroupFs {
+    pub fn new(root: impl Into<PathBuf>, path: impl AsRef<Path>) -> Self {
+        let path = Path::new("/").join(path);
+        CgroupFs { root: root.into(), path }
+    }
+
+    // The given cgroup, or this process's own, checked to be a cgroup v2 group
+    pub fn resolve(root: impl Into<PathBuf>, path: Option<&Path>, procfs: &ProcFs) -> Result<Self> {
+        let root = root.into();
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match procfs.cgroup() {
+                Some(path) => path,
+                None => bail!("this process isn't in a cgroup v2 hierarchy; pass the cgroup's path"),
+            },
+        };
+        let cgroup = CgroupFs::new(root, path);
+        if cgroup.read().is_none() {
+            bail!("no cgroup v2 group at {} (cpu.stat is missing)", cgroup.dir().display());
+        }
+        Ok(cgroup)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.root.join(self.path.strip_prefix("/").unwrap_or(&self.path))
+    }
+
+    fn read_file(&self, name: &str) -> Option<String> {
+        fs::read_to_string(self.dir().join(name)).ok()
+    }
+
+    // Everything at once; None if the cgroup is gone or isn't a v2 group
+    pub fn read(&self) -> Option<CgroupReading> {
+        let cpu_stat = self.read_file("cpu.stat")?;
+        let memory_events = self.read_file("memory.events").unwrap_or_default();
+        let (io_read_bytes, io_written_bytes) = self.read_file("io.stat").map_or((0, 0), |io| parse_io_stat(&io));
+        Some(CgroupReading {
+            cpu_usage_usec: field(&cpu_stat, "usage_usec")?,
+            // Only there with the cpu controller enabled
+            throttled_usec: field(&cpu_stat, "throttled_usec").unwrap_or(0),
+            cpu_limit: self.read_file("cpu.max").and_then(|max| parse_cpu_max(&max)),
+            memory_current: self.read_file("memory.current").and_then(|current| current.trim().parse().ok()),
+            memory_max: self.read_file("memory.max").and_then(|max| max.trim().parse().ok()),
+            oom_kills: field(&memory_events, "oom_kill").unwrap_or(0),
+            io_read_bytes,
+            io_written_bytes,
+        })
+    }
+}
+
+// "quota period" in microseconds, or "max period" for no limit
+fn parse_cpu_max(text: &str) -> Option<f32> {
+    let mut parts = text.split_whitespace();
+    let quota: f32 = parts.next()?.parse().ok()?;
+    let period: f32 = parts.next()?.parse().ok()?;
+    (period > 0.0).then(|| quota / period)
+}
+
+// "8:0 rbytes=1 wbytes=2 rios=3 wios=4 dbytes=0 dios=0", one line per device
+fn parse_io_stat(text: &str) -> (u64, u64) {
+    let value = |line: &str, key: &str| -> u64 {
+        line.split_whitespace()
+            .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    };
+    text.lines().fold((0, 0), |(read, written), line| {
+        (read + value(line, "rbytes"), written + value(line, "wbytes"))
+    })
+}
+
+// What the cgroup used over the last sample, measured against its own limits
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupStats {
+    pub path: String,
+    // Share of the CPU limit used, 0-100%
+    pub cpu_usage: f32,
+    // CPUs' worth of time the cgroup may use: its quota, capped at every CPU
+    pub cpu_limit: f32,
+    // Share of the time the cgroup was held back by its quota, 0.0 - 1.0
+    pub throttled: f32,
+    pub memory_current: u64,
+    // memory.max, or all of the machine's memory when unlimited
+    pub memory_limit: u64,
+    pub memory_usage: f32,
+    pub oom_kills: u64,
+    // Bytes per second
+    pub io_read_rate: f32,
+    pub io_write_rate: f32,
+}
+
+impl CgroupStats {
+    // Usage between an earlier reading and this one, against the cgroup's
+    // limits or, where it has none, the machine's `cpus` and `total_memory`.
+    // The root cgroup is the whole machine, so it uses `used_memory`.
+    pub fn new(
+        path: &Path,
+        now: &CgroupReading,
+        earlier: Option<&CgroupReading>,
+        elapsed: f32,
+        cpus: usize,
+        (used_memory, total_memory): (u64, u64),
+    ) -> Self {
+        let elapsed = elapsed.max(f32::EPSILON);
+        let rate = |now: u64, earlier: Option<u64>| {
+            earlier.map_or(0.0, |earlier| now.saturating_sub(earlier) as f32 / elapsed)
+        };
+        // A quota above the machine's CPUs can never be used up
+        let cpus = cpus.max(1) as f32;
+        let cpu_limit = now.cpu_limit.map_or(cpus, |limit| limit.min(cpus));
+        let cpu_seconds = rate(now.cpu_usage_usec, earlier.map(|e| e.cpu_usage_usec)) / 1.0e6;
+        let throttled = rate(now.throttled_usec, earlier.map(|e| e.throttled_usec)) / 1.0e6;
+        let memory_current = now.memory_current.unwrap_or(used_memory);
+        let memory_limit = now.memory_max.unwrap_or(total_memory);
+        CgroupStats {
+            path: path.display().to_string(),
+            cpu_usage: (cpu_seconds / cpu_limit * 100.0).clamp(0.0, 100.0),
+            cpu_limit,
+            throttled: throttled.clamp(0.0, 1.0),
+            memory_current,
+            memory_limit,
+            memory_usage: (memory_current as f32 / memory_limit.max(1) as f32 * 100.0).clamp(0.0, 100.0),
+            oom_kills: now.oom_kills,
+            io_read_rate: rate(now.io_read_bytes, earlier.map(|e| e.io_read_bytes)),
+            io_write_rate: rate(now.io_written_bytes, earlier.map(|e| e.io_written_bytes)),
+        }
+    }
+}
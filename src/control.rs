@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Automatic rod control: a PID loop that positions the rods to hold the core
+// temperature at a setpoint. The machine's load no longer moves the rods, it
+// disturbs the core and the controller has to reject it.
+
+// Seconds the derivative is smoothed over, so particle noise in the
+// temperature doesn't rattle the rods
+const DERIVATIVE_FILTER: f32 = 0.5;
+
+// Setpoint limits, °C; the floor is the cold core, the top is short of meltdown
+const SETPOINT_RANGE: (f32, f32) = (250.0, 900.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PidTuning {
+    pub setpoint: f32,    // core temperature to hold, °C
+    pub kp: f32,          // rod travel per °C of error
+    pub ki: f32,          // rod travel per °C·s of accumulated error
+    pub kd: f32,          // rod travel per °C/s the temperature is changing
+    pub rate_limit: f32,  // fastest the rods are driven, fraction of travel per second
+}
+
+impl Default for PidTuning {
+    fn default() -> Self {
+        PidTuning {
+            setpoint: 500.0,
+            kp: 0.002,
+            ki: 0.0005,
+            kd: 0.001,
+            rate_limit: 0.2,
+        }
+    }
+}
+
+// The tuning values, in the order they are cycled through at runtime
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PidParameter {
+    #[default]
+    Setpoint,
+    Kp,
+    Ki,
+    Kd,
+    RateLimit,
+}
+
+impl PidParameter {
+    pub const ALL: [PidParameter; 5] = [
+        PidParameter::Setpoint,
+        PidParameter::Kp,
+        PidParameter::Ki,
+        PidParameter::Kd,
+        PidParameter::RateLimit,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    // How far one key press moves it
+    fn step(self) -> f32 {
+        match self {
+            PidParameter::Setpoint => 10.0,
+            PidParameter::Kp => 0.0005,
+            PidParameter::Ki => 0.0001,
+            PidParameter::Kd => 0.0005,
+            PidParameter::RateLimit => 0.05,
+        }
+    }
+
+    pub fn value(self, tuning: &PidTuning) -> f32 {
+        match self {
+            PidParameter::Setpoint => tuning.setpoint,
+            PidParameter::Kp => tuning.kp,
+            PidParameter::Ki => tuning.ki,
+            PidParameter::Kd => tuning.kd,
+            PidParameter::RateLimit => tuning.rate_limit,
+        }
+    }
+
+    // Move it `steps` key presses, keeping it in a sensible range
+    pub fn adjust(self, tuning: &mut PidTuning, steps: f32) {
+        let value = self.value(tuning) + steps * self.step();
+        match self {
+            PidParameter::Setpoint => tuning.setpoint = value.clamp(SETPOINT_RANGE.0, SETPOINT_RANGE.1),
+            PidParameter::Kp => tuning.kp = value.max(0.0),
+            PidParameter::Ki => tuning.ki = value.max(0.0),
+            PidParameter::Kd => tuning.kd = value.max(0.0),
+            PidParameter::RateLimit => tuning.rate_limit = value.clamp(0.05, 1.0),
+        }
+    }
+}
+
+impl fmt::Display for PidParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PidParameter::Setpoint => write!(f, "Setpoint"),
+            PidParameter::Kp => write!(f, "Kp"),
+            PidParameter::Ki => write!(f, "Ki"),
+            PidParameter::Kd => write!(f, "Kd"),
+            PidParameter::RateLimit => write!(f, "Rate limit"),
+        }
+    }
+}
+
+// What each part of the controller contributed on the last update, in rod
+// travel (0.0 = fully inserted, 1.0 = fully withdrawn)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PidTerms {
+    pub error: f32,  // setpoint - temperature, °C
+    pub proportional: f32,
+    pub integral: f32,
+    pub derivative: f32,
+    // Where the terms put the rods, and where the rods were actually sent
+    pub demand: f32,
+    pub output: f32,
+    // The demand was past the end of rod travel
+    pub saturated: bool,
+    // The rods couldn't be driven as fast as asked
+    pub rate_limited: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct PidController {
+    pub tuning: PidTuning,
+    pub terms: PidTerms,
+    integral: f32,
+    last_temperature: Option<f32>,
+    temperature_rate: f32,  // filtered, °C/s
+}
+
+impl PidController {
+    // Take over with the rods at `rod_position`, without moving them
+    pub fn new(tuning: PidTuning, rod_position: f32) -> Self {
+        let mut controller = PidController {
+            tuning,
+            terms: PidTerms::default(),
+            integral: 0.0,
+            last_temperature: None,
+            temperature_rate: 0.0,
+        };
+        controller.track(rod_position, None);
+        controller
+    }
+
+    // Follow rods that something else is moving (a SCRAM), so control picks
+    // up from wherever they end up without a jolt
+    pub fn track(&mut self, rod_position: f32, temperature: Option<f32>) {
+        let error = temperature.map_or(0.0, |t| self.tuning.setpoint - t);
+        self.integral = rod_position - self.tuning.kp * error;
+        self.last_temperature = temperature;
+        self.temperature_rate = 0.0;
+        self.terms = PidTerms {
+            error,
+            proportional: self.tuning.kp * error,
+            integral: self.integral,
+            demand: rod_position,
+            output: rod_position,
+            ..PidTerms::default()
+        };
+    }
+
+    // Where the rods should be after `dt` seconds, given the core temperature
+    pub fn update(&mut self, temperature: f32, dt: f32) -> f32 {
+        let tuning = self.tuning;
+        let error = tuning.setpoint - temperature;
+
+        // Derivative on the measurement rather than the error, so a setpoint
+        // change doesn't kick the rods
+        if let Some(last) = self.last_temperature {
+            let rate = (temperature - last) / dt.max(f32::EPSILON);
+            self.temperature_rate += (rate - self.temperature_rate) * (dt / DERIVATIVE_FILTER).min(1.0);
+        }
+        self.last_temperature = Some(temperature);
+
+        let proportional = tuning.kp * error;
+        let derivative = -tuning.kd * self.temperature_rate;
+
+        // Only integrate while that helps: once the rods are at an end stop,
+        // winding the integral further just delays recovery
+        let integral = self.integral + tuning.ki * error * dt;
+        let demand = proportional + integral + derivative;
+        let saturated = !(0.0..=1.0).contains(&demand);
+        if !saturated || (demand > 1.0) != (error > 0.0) {
+            self.integral = integral;
+        }
+        let demand = proportional + self.integral + derivative;
+
+        let max_travel = tuning.rate_limit * dt;
+        let clamped = demand.clamp(0.0, 1.0);
+        let output = self.terms.output + (clamped - self.terms.output).clamp(-max_travel, max_travel);
+
+        self.terms = PidTerms {
+            error,
+            proportional,
+            integral: self.integral,
+            derivative,
+            demand,
+            output,
+            saturated,
+            rate_limited: (output - clamped).abs() > f32::EPSILON,
+        };
+        output
+    }
+}
@@ -0,0 +1,83 @@
+// Fission products: the slow memory of the core. Both effects are fed by the
+// machine's CPU-seconds, so they reflect how hard it has been worked over the
+// last few minutes rather than its load right now.
+
+// Decay heat groups: each is an exponentially windowed integral of CPU-seconds
+// (per core) with its own time constant (s), and its share of full power when
+// the core has been at full load for much longer than that
+const DECAY_HEAT_TIMES: [f32; 3] = [5.0, 30.0, 180.0];
+const DECAY_HEAT_SHARES: [f32; 3] = [0.05, 0.04, 0.03];
+
+// Iodine decays into xenon, which decays on its own and is burnt out by
+// neutron flux (i.e. load). Real half-lives are hours; these are scaled to
+// minutes so the effect is visible.
+const IODINE_TIME: f32 = 120.0;
+const XENON_TIME: f32 = 180.0;
+// Burnout rate (1/s) at full load
+const XENON_BURNOUT: f32 = 1.0 / 60.0;
+
+// Window of the "recent work" figure shown in the status panel
+pub const RECENT_WORK_WINDOW: f32 = 600.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct FissionProducts {
+    // CPU-seconds over the whole session, summed across cores
+    pub cpu_seconds: f64,
+    // CPU-seconds over roughly the last RECENT_WORK_WINDOW
+    pub recent_cpu_seconds: f32,
+    // Per-core CPU-seconds in each decay heat group's window
+    decay_groups: [f32; 3],
+    iodine: f32,
+    xenon: f32,
+}
+
+impl FissionProducts {
+    pub fn new() -> Self {
+        FissionProducts::default()
+    }
+
+    // Account for `dt` seconds at the given per-core loads (0-100%)
+    pub fn update(&mut self, core_loads: &[f32], dt: f32) {
+        let cores = core_loads.len().max(1) as f32;
+        let cpu_seconds: f32 = core_loads.iter().map(|load| load.clamp(0.0, 100.0) / 100.0).sum::<f32>() * dt;
+        self.cpu_seconds += cpu_seconds as f64;
+        self.recent_cpu_seconds += cpu_seconds - self.recent_cpu_seconds / RECENT_WORK_WINDOW * dt;
+
+        // Everything below works per core, so a big machine isn't poisoned
+        // just for having more cores
+        let per_core = cpu_seconds / cores;
+        let load = per_core / dt;
+        for (group, time) in self.decay_groups.iter_mut().zip(DECAY_HEAT_TIMES) {
+            *group += per_core - *group / time * dt;
+        }
+
+        let iodine_decay = self.iodine / IODINE_TIME * dt;
+        self.iodine += per_core - iodine_decay;
+        self.xenon += iodine_decay - self.xenon / XENON_TIME * dt - XENON_BURNOUT * load * self.xenon * dt;
+    }
+
+    // Heat still coming out of the fuel, as a fraction of full power
+    pub fn decay_heat(&self) -> f32 {
+        self.decay_groups
+            .iter()
+            .zip(DECAY_HEAT_TIMES)
+            .zip(DECAY_HEAT_SHARES)
+            .map(|((group, time), share)| share * group / time)
+            .sum()
+    }
+
+    // Xenon poisoning, 0.0 (clean) to 1.0: the level a core idling forever
+    // after being fed at full load without any burnout would settle at
+    pub fn xenon(&self) -> f32 {
+        (self.xenon / XENON_TIME).clamp(0.0, 1.0)
+    }
+
+    // A fresh core has no fission products; the session's CPU-seconds stay
+    pub fn reset(&mut self) {
+        *self = FissionProducts {
+            cpu_seconds: self.cpu_seconds,
+            recent_cpu_seconds: self.recent_cpu_seconds,
+            ..FissionProducts::default()
+        };
+    }
+}
@@ -0,0 +1,76 @@
+use crate::procfs::DiskCounters;
+use serde::{Deserialize, Serialize};
+
+// I/O on one block device: rates over the last sample
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskStats {
+    pub name: String,
+    // Bytes and operations per second
+    pub read_rate: f32,
+    pub write_rate: f32,
+    pub read_iops: f32,
+    pub write_iops: f32,
+    // Share of the time the device had I/O in flight, 0.0 - 1.0
+    pub utilization: f32,
+}
+
+impl DiskStats {
+    // Rates between an earlier reading and this one; without an earlier one
+    // (a new device) they read as zero, as does a counter that went backwards
+    pub fn new(name: &str, now: &DiskCounters, earlier: Option<&DiskCounters>, elapsed: f32) -> Self {
+        let elapsed = elapsed.max(f32::EPSILON);
+        let rate = |now: u64, earlier: Option<u64>| {
+            earlier.map_or(0.0, |earlier| now.saturating_sub(earlier) as f32 / elapsed)
+        };
+        DiskStats {
+            name: name.to_string(),
+            read_rate: rate(now.read_bytes, earlier.map(|e| e.read_bytes)),
+            write_rate: rate(now.written_bytes, earlier.map(|e| e.written_bytes)),
+            read_iops: rate(now.reads, earlier.map(|e| e.reads)),
+            write_iops: rate(now.writes, earlier.map(|e| e.writes)),
+            utilization: (rate(now.busy_ms, earlier.map(|e| e.busy_ms)) / 1000.0).clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn iops(&self) -> f32 {
+        self.read_iops + self.write_iops
+    }
+
+    // Device-mapper and md (software RAID) devices pass their I/O on to the
+    // disks under them, which report it again
+    pub fn is_stacked(&self) -> bool {
+        self.name.starts_with("dm-") || self.name.starts_with("md")
+    }
+
+    // Loop devices are backed by a file on another disk, which reports their
+    // I/O again; zram and ram disks are memory rather than storage
+    pub fn is_virtual(&self) -> bool {
+        ["loop", "zram", "ram"].iter().any(|prefix| self.name.starts_with(prefix))
+    }
+}
+
+// Bytes per second read and written across all storage, each byte counted once
+pub fn total_rates(disks: &[DiskStats]) -> (f32, f32) {
+    disks
+        .iter()
+        .filter(|disk| !disk.is_stacked() && !disk.is_virtual())
+        .fold((0.0, 0.0), |(read, written), disk| (read + disk.read_rate, written + disk.write_rate))
+}
+
+// Space on one mounted filesystem
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilesystemUsage {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl FilesystemUsage {
+    // Share of the filesystem in use, 0.0 - 1.0
+    pub fn usage(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        1.0 - self.available_bytes.min(self.total_bytes) as f32 / self.total_bytes as f32
+    }
+}
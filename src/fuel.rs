@@ -0,0 +1,146 @@
+use rand::Rng;
+
+// A square lattice of fuel pellets filling the core circle. Neutrons that hit
+// a pellet split it, and every pellet slowly burns up as the machine works.
+
+// The core circle drawn by the UI, centred on (0.5, 0.5)
+pub const CORE_RADIUS: f32 = 0.25;
+// Distance between neighbouring pellets, and how many fit either side of the centre
+const PELLET_PITCH: f32 = 0.06;
+const HALF_WIDTH: i32 = 4;
+// A neutron whose centre passes this close to a pellet hits it
+pub const PELLET_RADIUS: f32 = 0.012;
+
+// Seconds after splitting before a pellet can split again; long enough that
+// a chain reaction visibly spreads across the lattice
+const PELLET_RECHARGE: f32 = 0.5;
+
+// Per-core CPU-seconds of full load that burn a fresh lattice out, plus the
+// extra fuel each fission uses, so the busiest pellets go first
+const FUEL_LIFETIME: f32 = 3600.0;
+const FISSION_BURNUP: f32 = 0.0002;
+// Below this a pellet no longer splits
+pub const SPENT_FUEL: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pellet {
+    pub x: f32,
+    pub y: f32,
+    pub fuel: f32,      // 1.0 = fresh, 0.0 = completely burnt up
+    pub recharge: f32,  // seconds until it can split again
+}
+
+impl Pellet {
+    pub fn is_spent(&self) -> bool {
+        self.fuel < SPENT_FUEL
+    }
+
+    // Split within the last PELLET_RECHARGE seconds
+    pub fn is_flashing(&self) -> bool {
+        self.recharge > 0.0
+    }
+
+    pub fn can_split(&self) -> bool {
+        !self.is_spent() && !self.is_flashing()
+    }
+}
+
+pub struct FuelLattice {
+    pub pellets: Vec<Pellet>,
+    // Index into `pellets` for every lattice site, row by row; None outside the core
+    sites: Vec<Option<usize>>,
+    // Over the whole session
+    pub fissions: u64,
+}
+
+impl FuelLattice {
+    pub fn new() -> Self {
+        let mut pellets = Vec::new();
+        let mut sites = Vec::new();
+        for row in -HALF_WIDTH..=HALF_WIDTH {
+            for col in -HALF_WIDTH..=HALF_WIDTH {
+                let (dx, dy) = (col as f32 * PELLET_PITCH, row as f32 * PELLET_PITCH);
+                if (dx * dx + dy * dy).sqrt() + PELLET_RADIUS > CORE_RADIUS {
+                    sites.push(None);
+                    continue;
+                }
+                sites.push(Some(pellets.len()));
+                pellets.push(Pellet {
+                    x: 0.5 + dx,
+                    y: 0.5 + dy,
+                    fuel: 1.0,
+                    recharge: 0.0,
+                });
+            }
+        }
+
+        FuelLattice {
+            pellets,
+            sites,
+            fissions: 0,
+        }
+    }
+
+    // Burn fuel for `cpu_seconds` of per-core work and let split pellets recharge
+    pub fn update(&mut self, cpu_seconds: f32, dt: f32) {
+        let burnup = cpu_seconds / FUEL_LIFETIME;
+        for pellet in &mut self.pellets {
+            pellet.fuel = (pellet.fuel - burnup).max(0.0);
+            pellet.recharge = (pellet.recharge - dt).max(0.0);
+        }
+    }
+
+    // The pellet under a point, if there is one close enough to hit
+    pub fn pellet_at(&self, x: f32, y: f32) -> Option<usize> {
+        let col = ((x - 0.5) / PELLET_PITCH).round() as i32;
+        let row = ((y - 0.5) / PELLET_PITCH).round() as i32;
+        if col.abs() > HALF_WIDTH || row.abs() > HALF_WIDTH {
+            return None;
+        }
+
+        let width = 2 * HALF_WIDTH + 1;
+        let index = self.sites[((row + HALF_WIDTH) * width + col + HALF_WIDTH) as usize]?;
+        let pellet = &self.pellets[index];
+        let (dx, dy) = (x - pellet.x, y - pellet.y);
+        (dx * dx + dy * dy < PELLET_RADIUS * PELLET_RADIUS).then_some(index)
+    }
+
+    pub fn split(&mut self, index: usize) {
+        let pellet = &mut self.pellets[index];
+        pellet.fuel = (pellet.fuel - FISSION_BURNUP).max(0.0);
+        pellet.recharge = PELLET_RECHARGE;
+        self.fissions += 1;
+    }
+
+    // A random pellet that still has fuel, more likely the more it has left
+    pub fn random_fuelled(&self, rng: &mut impl Rng) -> Option<&Pellet> {
+        if self.pellets.iter().all(Pellet::is_spent) {
+            return None;
+        }
+        loop {
+            let pellet = &self.pellets[rng.random_range(0..self.pellets.len())];
+            if !pellet.is_spent() && rng.random::<f32>() < pellet.fuel {
+                return Some(pellet);
+            }
+        }
+    }
+
+    // Average fuel left across the lattice, 0.0 - 1.0
+    pub fn fuel_remaining(&self) -> f32 {
+        self.pellets.iter().map(|p| p.fuel).sum::<f32>() / self.pellets.len().max(1) as f32
+    }
+
+    // Replace every pellet with fresh fuel
+    pub fn refuel(&mut self) {
+        for pellet in &mut self.pellets {
+            pellet.fuel = 1.0;
+            pellet.recharge = 0.0;
+        }
+    }
+}
+
+impl Default for FuelLattice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,298 @@
+use crate::reactor::{Reactor, ReactorPhase, LOAD_DISTURBANCE};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Operator mode: the player drives the rods and pumps, and the machine's real
+// load is the disturbance they have to counter. Points come from keeping the
+// core in the safe stability band; a meltdown ends the game.
+
+// Stability scores (0-100) that count as safely producing power
+pub const SAFE_BAND: RangeInclusive<f32> = 20.0..=60.0;
+
+// Rods are moved in up to this many banks, each covering every Nth core
+pub const ROD_BANKS: usize = 4;
+// How far one key press moves a bank, and the pump demand
+const ROD_STEP: f32 = 0.05;
+const PUMP_STEP: f32 = 0.1;
+
+// Entries kept in the high-score file
+const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // Scales how strongly the machine's load pushes the core
+    pub fn load_gain(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.6,
+        }
+    }
+
+    // Points for each second in the safe band
+    fn points_per_second(self) -> f32 {
+        match self {
+            Difficulty::Easy => 5.0,
+            Difficulty::Normal => 10.0,
+            Difficulty::Hard => 20.0,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(format!("unknown difficulty '{}' (expected easy, normal or hard)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Normal => write!(f, "normal"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+// What the operator has set. While the reactor has these, its rods follow
+// the banks instead of the load.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorControls {
+    // Target position of each bank, 0.0 = fully inserted
+    pub banks: [f32; ROD_BANKS],
+    // Bank moved by the rod keys; None moves all of them
+    pub selected: Option<usize>,
+    // Fraction of the available pump flow asked for, 0.0 - 1.0
+    pub pump_demand: f32,
+    pub load_gain: f32,
+}
+
+impl OperatorControls {
+    // Banks start where the rods are, so taking over doesn't jolt the core
+    pub fn new(rod_position: f32, load_gain: f32) -> Self {
+        OperatorControls {
+            banks: [rod_position; ROD_BANKS],
+            selected: None,
+            pump_demand: 1.0,
+            load_gain,
+        }
+    }
+
+    // The bank driving a rod, given how many rods there are
+    pub fn bank_of(rod: usize, rods: usize) -> usize {
+        rod % rods.clamp(1, ROD_BANKS)
+    }
+
+    pub fn move_rods(&mut self, steps: f32) {
+        let selected = self.selected;
+        for (bank, position) in self.banks.iter_mut().enumerate() {
+            if selected.is_none_or(|selected| selected == bank) {
+                *position = (*position + steps * ROD_STEP).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    pub fn adjust_pumps(&mut self, steps: f32) {
+        self.pump_demand = (self.pump_demand + steps * PUMP_STEP).clamp(0.0, 1.0);
+    }
+
+    // How much further out the load effectively pulls the rods, for a
+    // load of 0.0 - 1.0
+    pub fn disturbance(&self, load: f32) -> f32 {
+        LOAD_DISTURBANCE * self.load_gain * load.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub difficulty: Difficulty,
+    pub seconds_in_band: f32,
+    // Unix time the game ended
+    pub achieved: u64,
+}
+
+// The local high-score table, kept as JSON
+pub struct HighScores {
+    path: PathBuf,
+    pub entries: Vec<HighScore>,
+    // False for a table standing in for a file that couldn't be read, so
+    // the file isn't overwritten
+    writable: bool,
+}
+
+impl HighScores {
+    // An empty table that is never saved
+    pub fn in_memory(path: impl AsRef<Path>) -> Self {
+        HighScores {
+            path: path.as_ref().to_path_buf(),
+            entries: Vec::new(),
+            writable: false,
+        }
+    }
+
+    // A missing file is an empty table
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("Invalid high-score file {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read high scores {}", path.display()));
+            }
+        };
+        Ok(HighScores { path, entries, writable: true })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn best(&self) -> Option<&HighScore> {
+        self.entries.first()
+    }
+
+    // Add a result and return its rank (0-based), if it made the table
+    pub fn record(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self.entries.iter().position(|e| entry.score > e.score).unwrap_or(self.entries.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if !self.writable {
+            bail!("Not overwriting unreadable high scores {}", self.path.display());
+        }
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let text = serde_json::to_string_pretty(&self.entries)?;
+        fs::write(&self.path, text)
+            .with_context(|| format!("Failed to write high scores {}", self.path.display()))
+    }
+}
+
+// ~/.nuclear-monitor-scores.json, or the working directory without a home
+pub fn default_high_score_path() -> PathBuf {
+    let file = ".nuclear-monitor-scores.json";
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(file),
+        None => PathBuf::from(file),
+    }
+}
+
+// The outcome of a finished game, for the game panel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameResult {
+    pub score: u32,
+    // Place in the high-score table (0-based), if it made it
+    pub rank: Option<usize>,
+}
+
+pub struct Game {
+    pub difficulty: Difficulty,
+    pub score: f32,
+    pub seconds_in_band: f32,
+    pub seconds_played: f32,
+    pub high_scores: HighScores,
+    pub last_result: Option<GameResult>,
+    // A problem reading or writing the high-score file
+    pub notice: Option<String>,
+    // Reactor meltdown count when this game started; a new one ends it
+    meltdowns: u32,
+}
+
+impl Game {
+    // A new game against the high scores in `path`. If they can't be read
+    // the game is still played, it just can't be saved.
+    pub fn new(difficulty: Difficulty, path: &Path, reactor: &Reactor) -> Self {
+        let (high_scores, notice) = match HighScores::load(path) {
+            Ok(high_scores) => (high_scores, None),
+            Err(err) => (HighScores::in_memory(path), Some(format!("{:#}", err))),
+        };
+        Game {
+            difficulty,
+            score: 0.0,
+            seconds_in_band: 0.0,
+            seconds_played: 0.0,
+            high_scores,
+            last_result: None,
+            notice,
+            meltdowns: reactor.meltdowns,
+        }
+    }
+
+    pub fn in_band(reactor: &Reactor) -> bool {
+        reactor.phase == ReactorPhase::Operating && SAFE_BAND.contains(&reactor.stability())
+    }
+
+    // Score `dt` seconds of play. A meltdown ends the game, and the next one
+    // starts straight away.
+    pub fn update(&mut self, reactor: &Reactor, dt: f32) {
+        if reactor.meltdowns != self.meltdowns {
+            self.finish();
+            self.meltdowns = reactor.meltdowns;
+            return;
+        }
+
+        self.seconds_played += dt;
+        if Self::in_band(reactor) {
+            self.seconds_in_band += dt;
+            self.score += self.difficulty.points_per_second() * dt;
+        }
+    }
+
+    // Enter the current game into the high-score table and start over. A
+    // game that scored nothing isn't entered.
+    pub fn finish(&mut self) {
+        let score = self.score as u32;
+        let seconds_in_band = self.seconds_in_band;
+        self.score = 0.0;
+        self.seconds_in_band = 0.0;
+        self.seconds_played = 0.0;
+        if score == 0 {
+            return;
+        }
+
+        let rank = self.high_scores.record(HighScore {
+            score,
+            difficulty: self.difficulty,
+            seconds_in_band,
+            achieved: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        });
+        if rank.is_some()
+            && let Err(err) = self.high_scores.save()
+        {
+            self.notice = Some(format!("{:#}", err));
+        }
+        self.last_result = Some(GameResult { score, rank });
+    }
+}
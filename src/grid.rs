@@ -0,0 +1,108 @@
+// Uniform-grid broad phase for particle collisions in the unit square.
+//
+// Particles are bucketed into square cells at least as wide as the largest
+// collision distance, so any two particles that can touch are in the same or
+// adjacent cells. Finding candidate pairs is then linear in the number of
+// particles instead of quadratic.
+pub struct SpatialGrid {
+    cols: usize,
+    cell_size: f32,
+    // `entries[cell_start[c]..cell_start[c + 1]]` are the particles in cell c
+    cell_start: Vec<usize>,
+    entries: Vec<u32>,
+    cell_of: Vec<usize>,
+}
+
+impl SpatialGrid {
+    // `min_cell_size` must be at least the largest distance at which two
+    // particles collide
+    pub fn new(min_cell_size: f32) -> Self {
+        let cols = ((1.0 / min_cell_size).floor() as usize).max(1);
+        SpatialGrid {
+            cols,
+            cell_size: 1.0 / cols as f32,
+            cell_start: vec![0; cols * cols + 1],
+            entries: Vec::new(),
+            cell_of: Vec::new(),
+        }
+    }
+
+    fn cell_coord(&self, v: f32) -> usize {
+        ((v / self.cell_size) as usize).min(self.cols - 1)
+    }
+
+    // Counting sort of particle indices by cell
+    pub fn rebuild(&mut self, positions: impl Iterator<Item = (f32, f32)>) {
+        self.cell_of.clear();
+        for (x, y) in positions {
+            let cell = self.cell_coord(y.max(0.0)) * self.cols + self.cell_coord(x.max(0.0));
+            self.cell_of.push(cell);
+        }
+
+        self.cell_start.iter_mut().for_each(|start| *start = 0);
+        for &cell in &self.cell_of {
+            self.cell_start[cell + 1] += 1;
+        }
+        for cell in 0..self.cols * self.cols {
+            self.cell_start[cell + 1] += self.cell_start[cell];
+        }
+
+        self.entries.clear();
+        self.entries.resize(self.cell_of.len(), 0);
+        let mut next = self.cell_start.clone();
+        for (index, &cell) in self.cell_of.iter().enumerate() {
+            self.entries[next[cell]] = index as u32;
+            next[cell] += 1;
+        }
+    }
+
+    fn cell(&self, col: usize, row: usize) -> &[u32] {
+        let cell = row * self.cols + col;
+        &self.entries[self.cell_start[cell]..self.cell_start[cell + 1]]
+    }
+
+    // Every pair of particles in the same or neighbouring cells, each pair
+    // once with the lower index first. The order only depends on positions,
+    // so it is deterministic.
+    pub fn candidate_pairs(&self, pairs: &mut Vec<(usize, usize)>) {
+        pairs.clear();
+        let cols = self.cols;
+
+        for row in 0..cols {
+            for col in 0..cols {
+                let here = self.cell(col, row);
+
+                for (k, &a) in here.iter().enumerate() {
+                    for &b in &here[k + 1..] {
+                        pairs.push(ordered(a, b));
+                    }
+                }
+
+                // Only look "forward" (right, and the row above) so every
+                // neighbouring pair of cells is visited exactly once
+                let neighbours = [
+                    (col + 1 < cols).then(|| (col + 1, row)),
+                    (row + 1 < cols && col > 0).then(|| (col - 1, row + 1)),
+                    (row + 1 < cols).then(|| (col, row + 1)),
+                    (row + 1 < cols && col + 1 < cols).then(|| (col + 1, row + 1)),
+                ];
+                for (ncol, nrow) in neighbours.into_iter().flatten() {
+                    let there = self.cell(ncol, nrow);
+                    for &a in here {
+                        for &b in there {
+                            pairs.push(ordered(a, b));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn ordered(a: u32, b: u32) -> (usize, usize) {
+    if a < b {
+        (a as usize, b as usize)
+    } else {
+        (b as usize, a as usize)
+    }
+}
@@ -1,4 +1,23 @@
 pub mod app;
 pub mod ui;
 pub mod system;
-pub mod reactor; 
\ No newline at end of file
+pub mod reactor;
+pub mod control;
+pub mod model;
+pub mod thermal;
+pub mod turbine;
+pub mod decay;
+pub mod fuel;
+pub mod game;
+pub mod grid;
+pub mod sensors;
+pub mod procfs;
+pub mod network;
+pub mod disk;
+pub mod psi;
+pub mod cgroup;
+pub mod sampler;
+pub mod metrics;
+pub mod scenario;
+pub mod stress;
+pub mod recording;
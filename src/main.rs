@@ -1,15 +1,266 @@
 use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use nuclear_monitor::app::{App, AppState};
+use nuclear_monitor::app::{App, AppConfig};
+use nuclear_monitor::cgroup::{CgroupFs, DEFAULT_CGROUP_ROOT};
+use nuclear_monitor::control::PidTuning;
+use nuclear_monitor::game::Difficulty;
+use nuclear_monitor::model::ModelKind;
+use nuclear_monitor::network::{InterfaceFilter, DEFAULT_EXCLUDED_INTERFACES};
+use nuclear_monitor::procfs::{ProcFs, DEFAULT_PROC_ROOT};
+use nuclear_monitor::metrics::{MetricsSource, Playback, SyntheticSource};
+use nuclear_monitor::recording::ReplaySource;
+use nuclear_monitor::reactor::{MeltdownCriteria, TemperatureSource, FIXED_TIMESTEP};
+use nuclear_monitor::sampler::{Sampler, SamplerConfig};
+use nuclear_monitor::scenario::{self, Blend, DEFAULT_SCENARIO_DIR};
+use nuclear_monitor::sensors::{TemperatureSensor, DEFAULT_SYS_ROOT};
+use nuclear_monitor::stress::StressConfig;
+use nuclear_monitor::thermal::PumpDrive;
 use nuclear_monitor::ui;
-use std::{io, time::{Duration, Instant}};
+use std::{io, path::PathBuf, time::{Duration, Instant}};
 use ratatui::{backend::CrosstermBackend, Terminal};
 
+#[derive(Parser)]
+#[command(name = "nuclears", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// CPU temperature sensor to read: package, hottest or core:N
+    #[arg(long, default_value_t = TemperatureSensor::Package)]
+    sensor: TemperatureSensor,
+
+    /// Drive the core temperature from the measured CPU temperature
+    #[arg(long)]
+    measured_temp: bool,
+
+    /// Metric sampling interval in milliseconds (independent of the frame rate)
+    #[arg(long, default_value_t = 500)]
+    interval: u64,
+
+    /// Process table rescan interval in milliseconds
+    #[arg(long, default_value_t = 5000)]
+    process_interval: u64,
+
+    /// Network interfaces that drive the turbine, as comma-separated patterns (* and ?); all when unset
+    #[arg(long, value_delimiter = ',')]
+    interfaces: Vec<String>,
+
+    /// Network interfaces to ignore, as comma-separated patterns; pass '' to ignore none
+    #[arg(long, value_delimiter = ',', default_values = DEFAULT_EXCLUDED_INTERFACES)]
+    exclude_interfaces: Vec<String>,
+
+    /// Where procfs is mounted, e.g. a host's /proc bind-mounted into a container
+    #[arg(long, default_value = DEFAULT_PROC_ROOT)]
+    proc_root: PathBuf,
+
+    /// Where sysfs is mounted, for temperature sensors when sysinfo finds none
+    #[arg(long, default_value = DEFAULT_SYS_ROOT)]
+    sys_root: PathBuf,
+
+    /// Monitor a cgroup v2 group instead of the whole machine: this process's own, or the given path
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    cgroup: Option<Option<PathBuf>>,
+
+    /// Where the cgroup v2 hierarchy is mounted
+    #[arg(long, default_value = DEFAULT_CGROUP_ROOT)]
+    cgroup_root: PathBuf,
+
+    /// Drive the reactor from a deterministic synthetic load instead of this machine
+    #[arg(long)]
+    synthetic: bool,
+
+    /// Render frame rate; the simulation runs at a fixed rate regardless
+    #[arg(long, default_value_t = 30)]
+    fps: u32,
+
+    /// Seed for the reactor simulation; the same seed and load trace replay identically
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Reactor physics: arcade or kinetics (point kinetics with delayed neutrons)
+    #[arg(long, default_value_t = ModelKind::Arcade)]
+    model: ModelKind,
+
+    /// What drives the coolant pumps: memory (free memory) or disk (disk I/O headroom)
+    #[arg(long, default_value_t = PumpDrive::Memory)]
+    pump_drive: PumpDrive,
+
+    /// Seconds over which the collision rate is averaged for the meltdown check
+    #[arg(long, default_value_t = 5.0)]
+    meltdown_window: f32,
+
+    /// Start in operator mode at this difficulty: easy, normal or hard
+    #[arg(long)]
+    game: Option<Difficulty>,
+
+    /// High-score file for operator mode [default: ~/.nuclear-monitor-scores.json]
+    #[arg(long)]
+    high_scores: Option<PathBuf>,
+
+    /// Start with a PID controller driving the rods to hold the core temperature
+    #[arg(long)]
+    pid: bool,
+
+    /// PID setpoint: core temperature to hold in °C [default: 500]
+    #[arg(long)]
+    setpoint: Option<f32>,
+
+    /// PID proportional gain, rod travel per °C of error [default: 0.002]
+    #[arg(long)]
+    kp: Option<f32>,
+
+    /// PID integral gain, rod travel per °C·s of error [default: 0.0005]
+    #[arg(long)]
+    ki: Option<f32>,
+
+    /// PID derivative gain, rod travel per °C/s of temperature change [default: 0.001]
+    #[arg(long)]
+    kd: Option<f32>,
+
+    /// Fastest the PID controller drives the rods, in fractions of full travel per second [default: 0.2]
+    #[arg(long)]
+    rod_rate: Option<f32>,
+
+    /// Play a load scenario from the start: a built-in name or a scenario file
+    #[arg(long)]
+    scenario: Option<String>,
+
+    /// How the scenario combines with the real load: override, add or max [default: the scenario's own]
+    #[arg(long)]
+    blend: Option<Blend>,
+
+    /// Directory of scenario files offered in the scenario menu
+    #[arg(long, default_value = DEFAULT_SCENARIO_DIR)]
+    scenario_dir: PathBuf,
+
+    /// Start the stress test straight away instead of waiting for 's'
+    #[arg(long)]
+    stress: bool,
+
+    /// Stress test CPU worker threads; 0 starts one per core
+    #[arg(long, default_value_t = 0)]
+    stress_workers: usize,
+
+    /// Target utilization of each stress worker in %, held with a work/sleep duty cycle
+    #[arg(long, default_value_t = 100.0)]
+    stress_load: f32,
+
+    /// Extra stress workers streaming through memory to load memory bandwidth
+    #[arg(long, default_value_t = 0)]
+    stress_memory_workers: usize,
+
+    /// Extra stress workers repeatedly allocating, touching and freeing memory
+    #[arg(long, default_value_t = 0)]
+    stress_alloc_workers: usize,
+
+    /// Memory each memory or allocation stress worker uses, in MiB
+    #[arg(long, default_value_t = 256)]
+    stress_memory_mb: usize,
+
+    /// Stop the stress test by itself after this many seconds
+    #[arg(long)]
+    stress_duration: Option<u64>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Monitor as usual while logging every snapshot to a JSONL file
+    Record {
+        path: PathBuf,
+    },
+    /// Feed a recorded session back through the reactor
+    Replay {
+        path: PathBuf,
+
+        /// Initial playback speed (0.25 - 16)
+        #[arg(long, default_value_t = 1.0)]
+        speed: f32,
+    },
+}
+
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let cgroup = cli.cgroup
+        .as_ref()
+        .map(|path| CgroupFs::resolve(&cli.cgroup_root, path.as_deref(), &ProcFs::new(&cli.proc_root)))
+        .transpose()?;
+    let mut config = AppConfig {
+        sampler: SamplerConfig {
+            interval: Duration::from_millis(cli.interval),
+            process_interval: Duration::from_millis(cli.process_interval),
+            sensor: cli.sensor,
+            interfaces: InterfaceFilter {
+                include: cli.interfaces.clone(),
+                exclude: cli.exclude_interfaces.iter().filter(|p| !p.is_empty()).cloned().collect(),
+            },
+            proc_root: cli.proc_root.clone(),
+            sys_root: cli.sys_root.clone(),
+            cgroup,
+        },
+        temperature_source: if cli.measured_temp {
+            TemperatureSource::Measured
+        } else {
+            TemperatureSource::Model
+        },
+        seed: cli.seed,
+        model: cli.model,
+        pump_drive: cli.pump_drive,
+        meltdown: MeltdownCriteria {
+            window: cli.meltdown_window.max(FIXED_TIMESTEP),
+            ..MeltdownCriteria::default()
+        },
+        game: cli.game,
+        pid: cli.pid,
+        pid_tuning: pid_tuning(&cli),
+        scenario: None,
+        scenario_dir: Some(cli.scenario_dir.clone()),
+        stress: StressConfig {
+            workers: cli.stress_workers,
+            utilization: cli.stress_load.clamp(0.0, 100.0),
+            memory_workers: cli.stress_memory_workers,
+            alloc_workers: cli.stress_alloc_workers,
+            memory_mb: cli.stress_memory_mb,
+            duration: cli.stress_duration.map(Duration::from_secs),
+        },
+        stress_on_start: cli.stress,
+        high_score_path: cli.high_scores,
+    };
+    if let Some(name) = &cli.scenario {
+        let (library, _) = scenario::library(&cli.scenario_dir);
+        let mut scenario = scenario::find(name, &library)?;
+        scenario.blend = cli.blend.unwrap_or(scenario.blend);
+        config.scenario = Some(scenario);
+    }
+
+    // Create app state before touching the terminal, so errors print normally
+    let app = match cli.command {
+        Some(Command::Replay { path, speed }) => {
+            let mut replay = ReplaySource::open(path)?;
+            replay.set_speed(speed);
+            // The session's own settings, so the replay runs exactly as it
+            // did. The stress test stays off, as it would load this machine,
+            // and scenario files are still looked for here.
+            if let Some(recorded) = replay.config() {
+                config = AppConfig {
+                    stress_on_start: false,
+                    scenario_dir: config.scenario_dir,
+                    ..recorded.clone()
+                };
+            }
+            App::with_source(Box::new(replay), config)
+        }
+        Some(Command::Record { path }) => {
+            let source = live_source(cli.synthetic, &config);
+            App::with_recording(source, config, path)?
+        }
+        None => App::with_source(live_source(cli.synthetic, &config), config),
+    };
+
     // Setup terminal
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = io::stdout();
@@ -17,11 +268,8 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app state
-    let app = App::new();
-    
     // Run the app
-    let res = run_app(&mut terminal, app);
+    let res = run_app(&mut terminal, app, cli.fps);
 
     // Restore terminal
     disable_raw_mode().context("Failed to disable raw mode")?;
@@ -39,12 +287,33 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+// Defaults for anything not given on the command line
+fn pid_tuning(cli: &Cli) -> PidTuning {
+    let default = PidTuning::default();
+    PidTuning {
+        setpoint: cli.setpoint.unwrap_or(default.setpoint),
+        kp: cli.kp.unwrap_or(default.kp),
+        ki: cli.ki.unwrap_or(default.ki),
+        kd: cli.kd.unwrap_or(default.kd),
+        rate_limit: cli.rod_rate.unwrap_or(default.rate_limit),
+    }
+}
+
+fn live_source(synthetic: bool, config: &AppConfig) -> Box<dyn MetricsSource> {
+    if synthetic {
+        let cores = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Box::new(SyntheticSource::new(cores))
+    } else {
+        Box::new(Sampler::system(config.sampler.clone()))
+    }
+}
+
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     mut app: App,
+    fps: u32,
 ) -> Result<()> {
-    // Increase frame rate to ~30 FPS for smoother animation
-    let tick_rate = Duration::from_millis(33); // 33ms ≈ 30 FPS
+    let tick_rate = Duration::from_secs(1) / fps.max(1);
     let mut last_tick = Instant::now();
 
     loop {
@@ -52,22 +321,23 @@ fn run_app(
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => {
-                        return Ok(());
-                    }
-                    _ => app.handle_key(key),
+            .unwrap_or(Duration::ZERO);
+
+        if crossterm::event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+        {
+            match key.code {
+                KeyCode::Char('q') => {
+                    return Ok(());
                 }
+                _ => app.handle_key(key),
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
-            app.update();
-            last_tick = Instant::now();
+            let now = Instant::now();
+            app.update(now - last_tick);
+            last_tick = now;
         }
     }
 }
@@ -0,0 +1,246 @@
+use crate::app::Action;
+use crate::disk::{DiskStats, FilesystemUsage};
+use crate::network::InterfaceStats;
+use crate::psi::{PressureAverages, PressureStall, SystemPressure};
+use crate::system::SystemInfo;
+use std::time::Duration;
+
+// Anything that can feed the reactor: the host via sysinfo, a background
+// sampler, a synthetic generator, a script, or your own service's metrics
+pub trait MetricsSource: Send {
+    // A fresh snapshot, or None if nothing new is available yet
+    fn sample(&mut self) -> Option<SystemInfo>;
+
+    // Move a source with a clock of its own on by `dt` of simulated time.
+    // Sources that measure the real world ignore it.
+    fn advance(&mut self, _dt: Duration) {}
+
+    // A one-line description for the status panel, if the source has one
+    fn status(&self) -> Option<String> {
+        None
+    }
+
+    // Transport controls for sources that play back a recording
+    fn playback(&self) -> Option<&dyn Playback> {
+        None
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        None
+    }
+}
+
+impl MetricsSource for Box<dyn MetricsSource> {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        (**self).sample()
+    }
+
+    fn advance(&mut self, dt: Duration) {
+        (**self).advance(dt)
+    }
+
+    fn status(&self) -> Option<String> {
+        (**self).status()
+    }
+
+    fn playback(&self) -> Option<&dyn Playback> {
+        (**self).playback()
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        (**self).playback_mut()
+    }
+}
+
+// Pause, seek and speed control over a recorded timeline
+pub trait Playback {
+    fn position(&self) -> Duration;
+    fn duration(&self) -> Duration;
+    fn speed(&self) -> f32;
+    fn is_paused(&self) -> bool;
+    fn is_finished(&self) -> bool {
+        self.position() >= self.duration()
+    }
+
+    fn toggle_pause(&mut self);
+    // Operator actions recorded just before the step at the playback position
+    fn actions(&self) -> Vec<Action>;
+    // Move the playback position on by one simulation step
+    fn step(&mut self);
+    // Jump to an absolute position, clamped to the recording
+    fn seek(&mut self, position: Duration);
+    // Playback speed, clamped to MIN_PLAYBACK_SPEED..=MAX_PLAYBACK_SPEED
+    fn set_speed(&mut self, speed: f32);
+
+    fn seek_by(&mut self, seconds: f32) {
+        let target = (self.position().as_secs_f32() + seconds).max(0.0);
+        self.seek(Duration::from_secs_f32(target));
+    }
+}
+
+pub const MIN_PLAYBACK_SPEED: f32 = 0.25;
+pub const MAX_PLAYBACK_SPEED: f32 = 16.0;
+
+// Deterministic load generator: every core follows a slow sine wave with its
+// own phase. The waves run on simulated time, so the same time always yields
+// the same snapshot, however often it is sampled.
+pub struct SyntheticSource {
+    cores: usize,
+    // One full load cycle
+    period: Duration,
+    elapsed: Duration,
+}
+
+impl SyntheticSource {
+    pub fn new(cores: usize) -> Self {
+        SyntheticSource {
+            cores: cores.max(1),
+            period: Duration::from_secs(20),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.period = period.max(Duration::from_millis(1));
+        self
+    }
+}
+
+impl MetricsSource for SyntheticSource {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        // Only the position within the cycle matters, which also keeps the
+        // phase precise however long the source has run
+        let cycle = (self.elapsed.as_nanos() % self.period.as_nanos()) as f64 / self.period.as_nanos() as f64;
+        let phase = std::f32::consts::TAU * cycle as f32;
+
+        let core_usage: Vec<f32> = (0..self.cores)
+            .map(|core| {
+                let offset = std::f32::consts::TAU * core as f32 / self.cores as f32;
+                57.5 + 37.5 * (phase + offset * 0.25).sin()
+            })
+            .collect();
+        let cpu_usage = core_usage.iter().sum::<f32>() / self.cores as f32;
+        let run_queue = cpu_usage / 100.0 * self.cores as f32;
+        // A bout of swapping at the top of every memory cycle
+        let thrashing = ((phase * 0.5).sin() - 0.8).max(0.0) * 5.0;
+
+        Some(SystemInfo {
+            cpu_usage,
+            core_usage,
+            memory_usage: 50.0 + 20.0 * (phase * 0.5).sin(),
+            disk_read_rate: 20.0e6 * (1.0 + (phase * 2.0).sin()),
+            disk_write_rate: 10.0e6 * (1.0 + (phase * 3.0).cos()),
+            swap_usage: 10.0 + 40.0 * thrashing,
+            load_average: [run_queue, run_queue * 0.9, run_queue * 0.8],
+            context_switch_rate: 20.0e3 * (1.0 + (phase * 1.5).sin()),
+            interrupt_rate: 5.0e3 * (1.0 + (phase * 1.1).sin()),
+            page_fault_rate: 10.0e3 * (1.0 + (phase * 0.7).cos()),
+            major_fault_rate: 20.0 + 1.0e3 * thrashing,
+            swap_in_rate: 3.0e3 * thrashing,
+            swap_out_rate: 4.0e3 * thrashing,
+            disks: vec![DiskStats {
+                name: "synthd0".to_string(),
+                read_rate: 20.0e6 * (1.0 + (phase * 2.0).sin()),
+                write_rate: 10.0e6 * (1.0 + (phase * 3.0).cos()),
+                read_iops: 400.0 * (1.0 + (phase * 2.0).sin()),
+                write_iops: 150.0 * (1.0 + (phase * 3.0).cos()),
+                utilization: 0.3 * (1.0 + (phase * 2.0).sin()),
+            }],
+            // Slowly filling up and being cleaned out again
+            filesystems: vec![FilesystemUsage {
+                mount_point: "/".to_string(),
+                total_bytes: 100 << 30,
+                available_bytes: ((30.0 + 20.0 * phase.cos()) * (1u64 << 30) as f32) as u64,
+            }],
+            pressure: SystemPressure {
+                cpu: Some(synthetic_stall(cpu_usage / 5.0, 0.0)),
+                memory: Some(synthetic_stall(2.0 * (1.0 + (phase * 0.5).sin()), 1.0 * (1.0 + (phase * 0.5).sin()))),
+                io: Some(synthetic_stall(8.0 * (1.0 + (phase * 2.0).sin()), 3.0 * (1.0 + (phase * 2.0).sin()))),
+            },
+            cgroup: None,
+            network: vec![InterfaceStats {
+                name: "synth0".to_string(),
+                rx_rate: 5.0e6 * (1.0 + (phase * 1.3).sin()),
+                tx_rate: 1.0e6 * (1.0 + (phase * 0.9).cos()),
+                rx_packet_rate: 4.0e3 * (1.0 + (phase * 1.3).sin()),
+                tx_packet_rate: 1.5e3 * (1.0 + (phase * 0.9).cos()),
+                ..InterfaceStats::default()
+            }],
+            cpu_temp: Some(40.0 + cpu_usage * 0.5),
+            cpu_temp_label: Some("synthetic".to_string()),
+            uptime: self.elapsed.as_secs(),
+            running_processes: 100,
+        })
+    }
+
+    fn advance(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+}
+
+// The same stall over every averaging window
+fn synthetic_stall(some: f32, full: f32) -> PressureStall {
+    let averages = |stall| PressureAverages { avg10: stall, avg60: stall, avg300: stall };
+    PressureStall {
+        some: averages(some),
+        full: Some(averages(full)),
+    }
+}
+
+// Plays back a fixed list of snapshots, one per sample, then holds the last
+// one (or starts over when looping)
+pub struct ScriptedSource {
+    frames: Vec<SystemInfo>,
+    position: usize,
+    looping: bool,
+}
+
+impl ScriptedSource {
+    pub fn new(frames: Vec<SystemInfo>) -> Self {
+        ScriptedSource {
+            frames,
+            position: 0,
+            looping: false,
+        }
+    }
+
+    // A script of whole-machine CPU loads (0-100%) on a single core
+    pub fn from_loads(loads: &[f32]) -> Self {
+        Self::new(
+            loads
+                .iter()
+                .map(|&load| SystemInfo {
+                    cpu_usage: load,
+                    core_usage: vec![load],
+                    ..SystemInfo::default()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.position >= self.frames.len()
+    }
+}
+
+impl MetricsSource for ScriptedSource {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        if self.position >= self.frames.len() {
+            if !self.looping {
+                return self.frames.last().cloned();
+            }
+            self.position = 0;
+        }
+        let frame = self.frames[self.position].clone();
+        self.position += 1;
+        Some(frame)
+    }
+}
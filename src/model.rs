@@ -0,0 +1,310 @@
+use crate::reactor::approach;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Rate (1/s) at which the core temperature eases towards its target
+const TEMPERATURE_SPEED: f32 = 0.9;
+const MAX_CORE_TEMPERATURE: f32 = 1000.0;
+
+// What a model sees on every simulation step
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ModelInput {
+    pub rod_position: f32,  // 0.0 = fully inserted, 1.0 = fully withdrawn, averaged over all rods
+    // 0.0 - 1.0, scaled by the difficulty in operator mode, so it can go higher
+    pub cpu_load: f32,
+    // Current core temperature, including heat from particle collisions
+    pub core_temperature: f32,
+    // Core temperature implied by the CPU sensor, when the reactor is driven by it
+    pub measured_temperature: Option<f32>,
+    // Slow fission product effects: decay heat as a fraction of full power,
+    // and xenon poisoning from 0.0 (clean) to 1.0
+    pub decay_heat: f32,
+    pub xenon: f32,
+    // Fuel left in the lattice, 1.0 = fresh; burnup weakens the core
+    pub fuel: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ModelOutput {
+    pub core_temperature: f32,
+    // Fraction of nominal power; can overshoot 1.0 in an excursion
+    pub power: f32,
+    // In dollars (units of the delayed neutron fraction); 0 for models without kinetics
+    pub reactivity: f32,
+    // Contribution to the stability score before random noise, 0-100
+    pub instability: f32,
+}
+
+// How the core responds to the rods and the load. The reactor owns the rods,
+// particles and lifecycle; a model only turns them into temperature and power.
+pub trait ReactorModel: Send {
+    fn kind(&self) -> ModelKind;
+    fn step(&mut self, input: &ModelInput, dt: f32) -> ModelOutput;
+    // Back to a cold, freshly loaded core
+    fn reset(&mut self);
+    // Extra lines for the status panel
+    fn details(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelKind {
+    // Everything follows the rod position directly
+    #[default]
+    Arcade,
+    // Neutron population from the point-kinetics equations
+    #[serde(rename = "point-kinetics")]
+    PointKinetics,
+}
+
+impl ModelKind {
+    pub fn build(self) -> Box<dyn ReactorModel> {
+        match self {
+            ModelKind::Arcade => Box::new(ArcadeModel),
+            ModelKind::PointKinetics => Box::new(PointKineticsModel::new()),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ModelKind::Arcade => ModelKind::PointKinetics,
+            ModelKind::PointKinetics => ModelKind::Arcade,
+        }
+    }
+}
+
+impl FromStr for ModelKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "arcade" => Ok(ModelKind::Arcade),
+            "kinetics" | "point-kinetics" => Ok(ModelKind::PointKinetics),
+            other => Err(format!("unknown model '{}' (expected arcade or kinetics)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ModelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelKind::Arcade => write!(f, "arcade"),
+            ModelKind::PointKinetics => write!(f, "kinetics"),
+        }
+    }
+}
+
+// How much of the rods' effect a fully poisoned core cancels out
+const ARCADE_XENON_SUPPRESSION: f32 = 0.8;
+
+// The original behaviour: temperature, power and instability are simple maps
+// of the rod position. Xenon makes the rods less effective, and decay heat
+// keeps the core warm after the load drops.
+pub struct ArcadeModel;
+
+impl ReactorModel for ArcadeModel {
+    fn kind(&self) -> ModelKind {
+        ModelKind::Arcade
+    }
+
+    fn step(&mut self, input: &ModelInput, dt: f32) -> ModelOutput {
+        let rods = input.rod_position * (1.0 - ARCADE_XENON_SUPPRESSION * input.xenon) * input.fuel;
+        let target = input
+            .measured_temperature
+            .unwrap_or(220.0 + 700.0 * (rods + input.decay_heat));
+
+        ModelOutput {
+            core_temperature: approach(input.core_temperature, target, TEMPERATURE_SPEED, dt),
+            power: rods * rods,
+            reactivity: 0.0,
+            instability: rods * 30.0,
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+// Six delayed neutron groups for U-235: fractions and decay constants (1/s)
+const DELAYED_FRACTIONS: [f32; 6] = [0.000215, 0.001424, 0.001274, 0.002568, 0.000748, 0.000273];
+const DECAY_CONSTANTS: [f32; 6] = [0.0124, 0.0305, 0.111, 0.301, 1.14, 3.01];
+// Prompt neutron generation time (s)
+const GENERATION_TIME: f32 = 1.0e-4;
+// The equations are stiff, so each step is integrated in sub-steps of at most this long
+const KINETICS_SUBSTEP: f32 = 1.0e-3;
+
+// Reactivity worths, in dollars. Rods are critical at ROD_CRITICAL; load is
+// an extra insertion on top of the rods. Fully withdrawn rods at full load
+// stay below prompt critical ($1).
+const ROD_WORTH: f32 = 0.8;
+const ROD_CRITICAL: f32 = 0.2;
+const LOAD_WORTH: f32 = 0.15;
+// Negative temperature feedback ($ per °C above the cold core)
+const TEMPERATURE_COEFFICIENT: f32 = -0.0012;
+// Reactivity of a fully poisoned core, and of a completely burnt-up one
+const XENON_WORTH: f32 = -1.0;
+const BURNUP_WORTH: f32 = -1.0;
+const COLD_CORE: f32 = 220.0;
+
+// A weak external source keeps a subcritical core from reading exactly zero
+const NEUTRON_SOURCE: f32 = 0.15;
+const MIN_POWER: f32 = 0.05;
+const MAX_POWER: f32 = 5.0;
+
+// Heat balance: the core settles at COLD_CORE + HEAT_PER_POWER * power, with
+// HEAT_TRANSFER (1/s) setting how fast
+const HEAT_PER_POWER: f32 = 700.0;
+const HEAT_TRANSFER: f32 = 0.5;
+
+// Simplified point-kinetics: neutron population n (1.0 = nominal power) and
+// six delayed-neutron precursor groups, driven by reactivity from the rods,
+// the CPU load, temperature feedback, xenon poisoning and fuel burnup
+pub struct PointKineticsModel {
+    population: f32,
+    precursors: [f32; 6],
+    // Last step's reactivity breakdown, in dollars
+    rod_reactivity: f32,
+    load_reactivity: f32,
+    feedback_reactivity: f32,
+    xenon_reactivity: f32,
+    burnup_reactivity: f32,
+    // e-folding time of the population in seconds; infinite when steady
+    period: f32,
+    // Heated by fission alone (or following the CPU sensor), so particle
+    // collisions warm the displayed core without feeding back into the physics
+    fuel_temperature: f32,
+}
+
+impl PointKineticsModel {
+    pub fn new() -> Self {
+        let mut model = PointKineticsModel {
+            population: MIN_POWER,
+            precursors: [0.0; 6],
+            rod_reactivity: 0.0,
+            load_reactivity: 0.0,
+            feedback_reactivity: 0.0,
+            xenon_reactivity: 0.0,
+            burnup_reactivity: 0.0,
+            period: f32::INFINITY,
+            fuel_temperature: COLD_CORE,
+        };
+        model.reset();
+        model
+    }
+
+    fn beta() -> f32 {
+        DELAYED_FRACTIONS.iter().sum()
+    }
+
+    pub fn reactivity(&self) -> f32 {
+        self.rod_reactivity + self.load_reactivity + self.feedback_reactivity
+            + self.xenon_reactivity + self.burnup_reactivity
+    }
+
+    pub fn period(&self) -> f32 {
+        self.period
+    }
+
+    // dn/dt = (rho - beta) / L * n + sum(lambda_i * C_i) + S
+    // dC_i/dt = beta_i / L * n - lambda_i * C_i
+    fn integrate(&mut self, reactivity: f32, dt: f32) {
+        let beta = Self::beta();
+        let rho = reactivity * beta;
+        let substeps = (dt / KINETICS_SUBSTEP).ceil().max(1.0);
+        let h = dt / substeps;
+
+        for _ in 0..substeps as usize {
+            let delayed: f32 = DECAY_CONSTANTS
+                .iter()
+                .zip(&self.precursors)
+                .map(|(lambda, c)| lambda * c)
+                .sum();
+            let dn = (rho - beta) / GENERATION_TIME * self.population + delayed + NEUTRON_SOURCE;
+
+            for ((c, beta_i), lambda) in self.precursors.iter_mut().zip(DELAYED_FRACTIONS).zip(DECAY_CONSTANTS) {
+                *c += h * (beta_i / GENERATION_TIME * self.population - lambda * *c);
+            }
+            self.population = (self.population + h * dn).clamp(0.0, MAX_POWER);
+        }
+    }
+}
+
+impl Default for PointKineticsModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReactorModel for PointKineticsModel {
+    fn kind(&self) -> ModelKind {
+        ModelKind::PointKinetics
+    }
+
+    fn step(&mut self, input: &ModelInput, dt: f32) -> ModelOutput {
+        self.rod_reactivity = ROD_WORTH * (input.rod_position - ROD_CRITICAL);
+        self.load_reactivity = LOAD_WORTH * input.cpu_load.max(0.0);
+        self.feedback_reactivity = TEMPERATURE_COEFFICIENT * (self.fuel_temperature - COLD_CORE).max(0.0);
+        self.xenon_reactivity = XENON_WORTH * input.xenon;
+        self.burnup_reactivity = BURNUP_WORTH * (1.0 - input.fuel.clamp(0.0, 1.0));
+
+        let before = self.population;
+        self.integrate(self.reactivity(), dt);
+        let growth = (self.population - before) / (before.max(f32::EPSILON) * dt);
+        self.period = if growth.abs() > 1.0e-4 { 1.0 / growth } else { f32::INFINITY };
+
+        // Fission and decay heat warm the fuel, unless the temperature comes
+        // from the CPU sensor
+        self.fuel_temperature = match input.measured_temperature {
+            Some(measured) => approach(self.fuel_temperature, measured, TEMPERATURE_SPEED, dt),
+            None => {
+                let target = COLD_CORE + HEAT_PER_POWER * (self.population + input.decay_heat);
+                approach(self.fuel_temperature, target, HEAT_TRANSFER, dt)
+            }
+        };
+        let core_temperature = approach(input.core_temperature, self.fuel_temperature, TEMPERATURE_SPEED, dt);
+
+        // A short positive period is what makes a real core hard to control
+        let excursion = if self.period > 0.0 { (10.0 / self.period).min(1.0) } else { 0.0 };
+        ModelOutput {
+            core_temperature: core_temperature.min(MAX_CORE_TEMPERATURE),
+            power: self.population,
+            reactivity: self.reactivity(),
+            instability: (self.population.min(1.0) * 20.0 + excursion * 20.0).min(40.0),
+        }
+    }
+
+    fn reset(&mut self) {
+        // Start at source level with the precursors in equilibrium
+        self.population = MIN_POWER;
+        for ((c, beta_i), lambda) in self.precursors.iter_mut().zip(DELAYED_FRACTIONS).zip(DECAY_CONSTANTS) {
+            *c = beta_i / (GENERATION_TIME * lambda) * self.population;
+        }
+        self.rod_reactivity = 0.0;
+        self.load_reactivity = 0.0;
+        self.feedback_reactivity = 0.0;
+        self.xenon_reactivity = 0.0;
+        self.burnup_reactivity = 0.0;
+        self.period = f32::INFINITY;
+        self.fuel_temperature = COLD_CORE;
+    }
+
+    fn details(&self) -> Vec<String> {
+        let period = if self.period.is_finite() && self.period.abs() < 1000.0 {
+            format!("{:.1}s", self.period)
+        } else {
+            "∞".to_string()
+        };
+        vec![
+            format!("Neutron population: {:.3} (period {})", self.population, period),
+            format!("Fuel temperature: {:.0}°C", self.fuel_temperature),
+            format!("Reactivity: ${:+.2}", self.reactivity()),
+            format!(
+                "  rods {:+.2} cpu {:+.2} T {:+.2} Xe {:+.2} fuel {:+.2}",
+                self.rod_reactivity, self.load_reactivity, self.feedback_reactivity,
+                self.xenon_reactivity, self.burnup_reactivity
+            ),
+        ]
+    }
+}
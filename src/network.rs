@@ -0,0 +1,106 @@
+use crate::procfs::InterfaceCounters;
+use serde::{Deserialize, Serialize};
+
+// Network interfaces to watch, by name pattern. `*` matches any run of
+// characters and `?` any single one, so "veth*" covers every container link.
+
+// Loopback traffic never leaves the machine, and virtual Ethernet pairs
+// double-count what their bridge already carries
+pub const DEFAULT_EXCLUDED_INTERFACES: &[&str] = &["lo", "veth*"];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceFilter {
+    // Interfaces to watch; empty watches all of them
+    pub include: Vec<String>,
+    // Interfaces to leave out, even if included
+    pub exclude: Vec<String>,
+}
+
+impl InterfaceFilter {
+    pub fn matches(&self, name: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name));
+        included && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+impl Default for InterfaceFilter {
+    fn default() -> Self {
+        InterfaceFilter {
+            include: Vec::new(),
+            exclude: DEFAULT_EXCLUDED_INTERFACES.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Position of the last `*` and where in the name it started matching, to
+    // backtrack to when a later part of the pattern doesn't fit
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// Traffic on one interface: rates over the last sample, error and drop
+// counts since it came up
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub name: String,
+    // Bytes and packets per second
+    pub rx_rate: f32,
+    pub tx_rate: f32,
+    pub rx_packet_rate: f32,
+    pub tx_packet_rate: f32,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_drops: u64,
+}
+
+impl InterfaceStats {
+    // Rates between an earlier reading and this one; without an earlier one
+    // (a new interface) they read as zero. A counter that went backwards (the
+    // interface was recreated) also reads as zero.
+    pub fn new(name: &str, now: &InterfaceCounters, earlier: Option<&InterfaceCounters>, elapsed: f32) -> Self {
+        let elapsed = elapsed.max(f32::EPSILON);
+        let rate = |now: u64, earlier: Option<u64>| {
+            earlier.map_or(0.0, |earlier| now.saturating_sub(earlier) as f32 / elapsed)
+        };
+        InterfaceStats {
+            name: name.to_string(),
+            rx_rate: rate(now.rx_bytes, earlier.map(|e| e.rx_bytes)),
+            tx_rate: rate(now.tx_bytes, earlier.map(|e| e.tx_bytes)),
+            rx_packet_rate: rate(now.rx_packets, earlier.map(|e| e.rx_packets)),
+            tx_packet_rate: rate(now.tx_packets, earlier.map(|e| e.tx_packets)),
+            rx_errors: now.rx_errors,
+            tx_errors: now.tx_errors,
+            rx_drops: now.rx_drops,
+            tx_drops: now.tx_drops,
+        }
+    }
+
+    pub fn throughput(&self) -> f32 {
+        self.rx_rate + self.tx_rate
+    }
+}
@@ -0,0 +1,233 @@
+use crate::psi::{PressureAverages, PressureStall, SystemPressure};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Kernel counters read straight from /proc, for what sysinfo doesn't report.
+// Only Linux has them; elsewhere every read fails and callers see None.
+pub const DEFAULT_PROC_ROOT: &str = "/proc";
+
+// Event counters, monotonic since boot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KernelCounters {
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub page_faults: u64,
+    // Page faults that had to wait for the disk
+    pub major_faults: u64,
+    // Pages read back from and written out to swap
+    pub swap_ins: u64,
+    pub swap_outs: u64,
+}
+
+// The same events, per second
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KernelRates {
+    pub context_switches: f32,
+    pub interrupts: f32,
+    pub page_faults: f32,
+    pub major_faults: f32,
+    pub swap_ins: f32,
+    pub swap_outs: f32,
+}
+
+impl KernelCounters {
+    // Events per second between an earlier reading and this one. A counter
+    // that went backwards (e.g. a different root) reads as zero.
+    pub fn rates_since(&self, earlier: &KernelCounters, elapsed: f32) -> KernelRates {
+        let elapsed = elapsed.max(f32::EPSILON);
+        let rate = |now: u64, earlier: u64| now.saturating_sub(earlier) as f32 / elapsed;
+        KernelRates {
+            context_switches: rate(self.context_switches, earlier.context_switches),
+            interrupts: rate(self.interrupts, earlier.interrupts),
+            page_faults: rate(self.page_faults, earlier.page_faults),
+            major_faults: rate(self.major_faults, earlier.major_faults),
+            swap_ins: rate(self.swap_ins, earlier.swap_ins),
+            swap_outs: rate(self.swap_outs, earlier.swap_outs),
+        }
+    }
+}
+
+// Per-interface counters from /proc/net/dev, monotonic since the interface came up
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_drops: u64,
+}
+
+// Per-device counters from /proc/diskstats, monotonic since boot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskCounters {
+    pub reads: u64,
+    pub read_bytes: u64,
+    pub writes: u64,
+    pub written_bytes: u64,
+    // Milliseconds the device had I/O in flight
+    pub busy_ms: u64,
+}
+
+// /proc/diskstats counts in 512-byte sectors, whatever the device's own sector size
+const DISKSTATS_SECTOR: u64 = 512;
+
+pub struct ProcFs {
+    root: PathBuf,
+}
+
+impl ProcFs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        ProcFs { root: root.into() }
+    }
+
+    pub fn counters(&self) -> Option<KernelCounters> {
+        let stat = fs::read_to_string(self.root.join("stat")).ok()?;
+        let vmstat = fs::read_to_string(self.root.join("vmstat")).ok()?;
+        Some(KernelCounters {
+            context_switches: field(&stat, "ctxt")?,
+            // The first "intr" value is the total; the rest break it down by line
+            interrupts: field(&stat, "intr")?,
+            page_faults: field(&vmstat, "pgfault")?,
+            major_faults: field(&vmstat, "pgmajfault")?,
+            swap_ins: field(&vmstat, "pswpin")?,
+            swap_outs: field(&vmstat, "pswpout")?,
+        })
+    }
+
+    // Every interface in /proc/net/dev, in the kernel's order
+    pub fn net_dev(&self) -> Option<Vec<(String, InterfaceCounters)>> {
+        let text = fs::read_to_string(self.root.join("net/dev")).ok()?;
+        // Two header lines, then "name: 8 receive columns, 8 transmit columns".
+        // Names can contain colons themselves (aliases like "eth0:1"), and
+        // big counters run straight on from the colon, so split at the last one.
+        let interfaces = text.lines().skip(2).filter_map(|line| {
+            let (name, columns) = line.rsplit_once(':')?;
+            let values: Vec<u64> = columns.split_whitespace().map(|v| v.parse().ok()).collect::<Option<_>>()?;
+            if values.len() < 16 {
+                return None;
+            }
+            Some((name.trim().to_string(), InterfaceCounters {
+                rx_bytes: values[0],
+                rx_packets: values[1],
+                rx_errors: values[2],
+                rx_drops: values[3],
+                tx_bytes: values[8],
+                tx_packets: values[9],
+                tx_errors: values[10],
+                tx_drops: values[11],
+            }))
+        });
+        Some(interfaces.collect())
+    }
+
+    // Every block device in /proc/diskstats that has done any I/O, in the
+    // kernel's order. Partitions are left out, since their I/O is already
+    // counted by the whole device.
+    pub fn diskstats(&self) -> Option<Vec<(String, DiskCounters)>> {
+        let text = fs::read_to_string(self.root.join("diskstats")).ok()?;
+        // "major minor name", then reads, merged, sectors, ms reading, writes,
+        // merged, sectors, ms writing, in flight, ms busy, ...
+        let devices: Vec<(String, DiskCounters)> = text.lines().filter_map(|line| {
+            let mut columns = line.split_whitespace().skip(2);
+            let name = columns.next()?;
+            let values: Vec<u64> = columns.map(|v| v.parse().ok()).collect::<Option<_>>()?;
+            if values.len() < 10 {
+                return None;
+            }
+            Some((name.to_string(), DiskCounters {
+                reads: values[0],
+                read_bytes: values[2] * DISKSTATS_SECTOR,
+                writes: values[4],
+                written_bytes: values[6] * DISKSTATS_SECTOR,
+                busy_ms: values[9],
+            }))
+        }).collect();
+
+        let is_partition = |name: &str| devices.iter().any(|(device, _)| is_partition_of(name, device));
+        let disks = devices.iter()
+            .filter(|(name, counters)| counters.reads + counters.writes > 0 && !is_partition(name))
+            .cloned()
+            .collect();
+        Some(disks)
+    }
+
+    // This process's cgroup v2 path, from the "0::/path" line of
+    // /proc/self/cgroup; None on a v1-only hierarchy
+    pub fn cgroup(&self) -> Option<PathBuf> {
+        let text = fs::read_to_string(self.root.join("self/cgroup")).ok()?;
+        text.lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .map(|path| Path::new(path.trim()).to_path_buf())
+    }
+
+    // Stalls on CPU, memory and I/O from /proc/pressure; each is None if the
+    // kernel doesn't report it
+    pub fn pressure(&self) -> SystemPressure {
+        let read = |resource: &str| {
+            let text = fs::read_to_string(self.root.join("pressure").join(resource)).ok()?;
+            parse_pressure(&text)
+        };
+        SystemPressure {
+            cpu: read("cpu"),
+            memory: read("memory"),
+            io: read("io"),
+        }
+    }
+}
+
+impl Default for ProcFs {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROC_ROOT)
+    }
+}
+
+// The value on a "name value" line, the format of /proc/stat and /proc/vmstat
+pub(crate) fn field(text: &str, name: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        if parts.next()? != name {
+            return None;
+        }
+        parts.next()?.parse().ok()
+    })
+}
+
+// Partitions are named after their device: sda1 on sda, and nvme0n1p1 on
+// nvme0n1, since a trailing digit there would be ambiguous
+fn is_partition_of(name: &str, device: &str) -> bool {
+    let Some(suffix) = name.strip_prefix(device) else {
+        return false;
+    };
+    let number = if device.ends_with(|c: char| c.is_ascii_digit()) {
+        suffix.strip_prefix('p').unwrap_or_default()
+    } else {
+        suffix
+    };
+    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+}
+
+// "some avg10=0.72 avg60=1.89 avg300=2.25 total=133898127", and a "full" line
+// like it. A "some" line is required; anything unparseable counts as missing.
+fn parse_pressure(text: &str) -> Option<PressureStall> {
+    let averages = |kind: &str| {
+        let line = text.lines().find(|line| line.split_whitespace().next() == Some(kind))?;
+        let value = |key: &str| {
+            line.split_whitespace()
+                .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))?
+                .parse()
+                .ok()
+        };
+        Some(PressureAverages {
+            avg10: value("avg10")?,
+            avg60: value("avg60")?,
+            avg300: value("avg300")?,
+        })
+    };
+    Some(PressureStall {
+        some: averages("some")?,
+        full: averages("full"),
+    })
+}
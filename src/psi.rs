@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+// Linux Pressure Stall Information: the share of time tasks were stalled
+// waiting on a resource. "some" is time at least one task was stalled, "full"
+// time all of them were at once. Kernels before 4.20, or built without PSI,
+// don't have it.
+
+// Stalled time in %, averaged over the last 10 s, 60 s and 300 s
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureStall {
+    pub some: PressureAverages,
+    // Not reported for CPU before Linux 5.13
+    pub full: Option<PressureAverages>,
+}
+
+impl PressureStall {
+    // Recent stall in %: time at least one task was waiting. Full stalls are
+    // already part of it, since every task waiting means some are.
+    pub fn stall(&self) -> f32 {
+        self.some.avg10.clamp(0.0, 100.0)
+    }
+}
+
+// Stalls on each resource; None where the kernel doesn't report it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemPressure {
+    pub cpu: Option<PressureStall>,
+    pub memory: Option<PressureStall>,
+    pub io: Option<PressureStall>,
+}
+
+impl SystemPressure {
+    pub fn resources(&self) -> [(&'static str, Option<&PressureStall>); 3] {
+        [("cpu", self.cpu.as_ref()), ("mem", self.memory.as_ref()), ("io", self.io.as_ref())]
+    }
+
+    // Recent stall on the most contended resource, in %, if PSI is available
+    pub fn stall(&self) -> Option<f32> {
+        self.resources()
+            .into_iter()
+            .filter_map(|(_, stall)| stall.map(PressureStall::stall))
+            .reduce(f32::max)
+    }
+}
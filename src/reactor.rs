@@ -1,43 +1,198 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::ops::Range;
+use crate::control::PidController;
+use crate::decay::FissionProducts;
+use crate::fuel::{FuelLattice, PELLET_RADIUS};
+use crate::game::OperatorControls;
+use crate::grid::SpatialGrid;
+use crate::model::{ModelInput, ModelKind, ReactorModel};
+use crate::thermal::CoolantLoop;
+use crate::turbine::Turbine;
 
-const HISTORY_SIZE: usize = 30;
-const MAX_PARTICLES: usize = 200;
-const FRAME_RATE_FACTOR: f32 = 0.33;
+// Temperature history samples kept for the chart
+pub const HISTORY_SIZE: usize = 30;
+const MAX_PARTICLES: usize = 5000;
 const COLLISION_RADIUS: f32 = 0.02;
 
-#[derive(Clone, Copy)]
+// The simulation always advances in steps of this many seconds, whatever the
+// frame rate; rendering interpolates between the last two steps
+pub const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+// Longest frame we catch up on - anything longer (suspend, debugger, a stalled
+// terminal) is dropped rather than run as one burst of steps
+const MAX_FRAME_TIME: f32 = 0.25;
+
+// Seconds between temperature history samples and explosion frames
+const HISTORY_INTERVAL: f32 = 0.1;
+const EXPLOSION_FRAME_TIME: f32 = 1.0 / 6.0;
+
+// Rate constant (1/s) for rods easing towards their targets
+const ROD_SPEED: f32 = 0.9;
+// When the rods don't follow the load, full load pushes the core as if every
+// rod were this much further out
+pub const LOAD_DISTURBANCE: f32 = 0.4;
+
+// Lifecycle: how long the meltdown lasts before the core is made safe, and
+// when a cooling core is allowed back into operation
+const MELTDOWN_DURATION: f32 = 5.0;
+const SCRAM_ROD_SPEED: f32 = 6.0;
+const COOLDOWN_SPEED: f32 = 0.5;
+const COOLDOWN_MIN_TIME: f32 = 3.0;
+const COOLDOWN_TEMPERATURE: f32 = 300.0;
+// Temperature (°C) a cooling core is held above per unit of decay heat
+const DECAY_HEAT_RISE: f32 = 700.0;
+
+// Collisions per second over the last this share of the meltdown window
+// drive the time-to-criticality estimate
+const RECENT_RATE_SHARE: f32 = 0.2;
+
+// How many pellet radii from its pellet's centre a spontaneous neutron starts
+const SPAWN_CLEARANCE: f32 = 1.5;
+
+// Neutrons released when a pellet splits, and the heat (°C) it gives the core
+const FISSION_NEUTRONS: Range<usize> = 2..4;
+const FISSION_HEAT: f32 = 0.5;
+
+// Particles per second from each non-neutron emitter when its metric is at
+// full scale, and what full scale is for each: disk throughput (bytes/s) for
+// alphas, context switches/s (or interrupts/s) for betas and page faults/s
+// for gammas
+const SPECIES_EMISSION_RATE: f32 = 20.0;
+const ALPHA_FULL_SCALE: f32 = 100.0e6;
+const BETA_FULL_SCALE: f32 = 100.0e3;
+const INTERRUPT_FULL_SCALE: f32 = 50.0e3;
+const GAMMA_FULL_SCALE: f32 = 50.0e3;
+
+// Thrashing unsettles the core whatever the CPU percentage says: pages
+// swapped in and out per second, or major faults per second, at which it is
+// at its worst, and how much instability that adds
+const SWAP_FULL_SCALE: f32 = 5.0e3;
+const MAJOR_FAULT_FULL_SCALE: f32 = 2.0e3;
+const THRASH_INSTABILITY: f32 = 40.0;
+// So does a run queue longer than there are cores; twice as long is the worst
+const OVERLOAD_INSTABILITY: f32 = 15.0;
+
+// Measured CPU temperatures are mapped linearly onto the core temperature scale:
+// an idle chip (30°C) reads as a cold core, a throttling chip (100°C) as a hot one
+const MEASURED_IDLE_CELSIUS: f32 = 30.0;
+const MEASURED_HOT_CELSIUS: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureSource {
+    // Core temperature follows the control rod position
+    #[default]
+    Model,
+    // Core temperature follows the measured CPU temperature
+    Measured,
+}
+
+// What a particle is, which sets how it moves and what it does at the casing.
+// Each species is emitted in proportion to a different metric, so the mix on
+// screen shows what kind of load the machine is under.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Species {
+    // Disk I/O: slow and heavy, absorbed by the first wall they reach
+    Alpha,
+    // Context switches: fast and light, scattered by the walls
+    Beta,
+    // Page faults: very fast, pass straight through the casing
+    Gamma,
+    // CPU load: bounce around the core, and collisions involving them fission
+    #[default]
+    Neutron,
+}
+
+impl Species {
+    pub const ALL: [Species; 4] = [Species::Alpha, Species::Beta, Species::Gamma, Species::Neutron];
+    
+    fn speed(self) -> f32 {
+        match self {
+            Species::Alpha => 0.4,
+            Species::Beta => 1.5,
+            Species::Gamma => 2.5,
+            Species::Neutron => 1.0,
+        }
+    }
+    
+    // Seconds
+    fn lifetime(self) -> Range<f32> {
+        match self {
+            Species::Alpha => 0.4..1.2,
+            Species::Beta => 1.5..4.0,
+            Species::Gamma | Species::Neutron => 2.0..6.0,
+        }
+    }
+    
+    // Multiplies the particle's energy, which doubles as its mass in collisions
+    fn mass(self) -> f32 {
+        match self {
+            Species::Alpha => 4.0,
+            Species::Beta => 0.2,
+            Species::Gamma => 0.05,
+            Species::Neutron => 1.0,
+        }
+    }
+    
+    // Fraction of speed kept when bouncing off a wall; None if the wall
+    // doesn't reflect them
+    fn wall_damping(self) -> Option<f32> {
+        match self {
+            Species::Beta => Some(0.5),
+            Species::Neutron => Some(0.8),
+            Species::Alpha | Species::Gamma => None,
+        }
+    }
+    
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Species::Alpha => "α",
+            Species::Beta => "β",
+            Species::Gamma => "γ",
+            Species::Neutron => "n",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Particle {
+    pub species: Species,
     pub x: f32,
     pub y: f32, 
-    pub velocity_x: f32,
+    pub velocity_x: f32,  // units per second
     pub velocity_y: f32,
-    pub lifetime: u8,
+    pub lifetime: f32,    // seconds left
     pub radius: f32,
     pub energy: f32,
+    // Position at the previous step, for interpolated rendering
+    prev_x: f32,
+    prev_y: f32,
 }
 
 impl Particle {
-    fn new(x: f32, y: f32, intensity: f32) -> Self {
-        let mut rng = rand::thread_rng();
-        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    // A neutron, as emitted by the core
+    pub fn new(x: f32, y: f32, intensity: f32, rng: &mut impl Rng) -> Self {
+        Self::of_species(Species::Neutron, x, y, intensity, rng)
+    }
+    
+    pub fn of_species(species: Species, x: f32, y: f32, intensity: f32, rng: &mut impl Rng) -> Self {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
         
         // Higher intensity increases particle speed and lifetime
-        // Scale speed down for higher frame rate
-        let base_speed = rng.gen_range(0.03..0.15) * FRAME_RATE_FACTOR;
+        let base_speed = rng.random_range(0.3..1.5) * species.speed();
         let speed = base_speed * (1.0 + intensity * 0.5);
         
-        // Calculate lifetime with overflow protection
-        // Ensure we stay within u8 bounds (0-255)
-        let base_lifetime: u8 = rng.gen_range(60..180);
-        let lifetime_boost: u8 = (intensity * 40.0).min(75.0) as u8;
-        // Use saturating_add to prevent overflow
-        let lifetime = base_lifetime.saturating_add(lifetime_boost);
+        let base_lifetime = rng.random_range(species.lifetime());
+        let lifetime_boost = (intensity * 1.3).min(2.5);
+        let lifetime = base_lifetime + lifetime_boost;
         
         // Higher intensity = more energetic particles
-        let energy = 0.5 + (intensity * 0.5);
+        let energy = (0.5 + (intensity * 0.5)) * species.mass();
         
         Particle {
+            species,
             x,
             y,
             velocity_x: angle.cos() * speed,
@@ -45,16 +200,33 @@ impl Particle {
             lifetime,
             radius: COLLISION_RADIUS,
             energy,
+            prev_x: x,
+            prev_y: y,
         }
     }
     
-    fn update(&mut self) {
-        self.x += self.velocity_x;
-        self.y += self.velocity_y;
+    fn update(&mut self, dt: f32) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+        self.x += self.velocity_x * dt;
+        self.y += self.velocity_y * dt;
+        self.lifetime = (self.lifetime - dt).max(0.0);
+        
+        // Alphas are stopped by the first wall they reach; gammas pass through
+        // the casing and carry on until they leave the screen
+        let Some(damping) = self.species.wall_damping() else {
+            let at_wall = self.x < self.radius || self.x > 1.0 - self.radius
+                || self.y < self.radius || self.y > 1.0 - self.radius;
+            let escaped = self.x < 0.0 || self.x > 1.0 || self.y < 0.0 || self.y > 1.0;
+            if (self.species == Species::Alpha && at_wall) || escaped {
+                self.lifetime = 0.0;
+            }
+            return;
+        };
         
         // Bounce off walls
         if self.x < self.radius || self.x > 1.0 - self.radius {
-            self.velocity_x = -self.velocity_x * 0.8; // Dampening factor
+            self.velocity_x = -self.velocity_x * damping;
             
             // Ensure particle stays within bounds
             if self.x < self.radius {
@@ -65,7 +237,7 @@ impl Particle {
         }
         
         if self.y < self.radius || self.y > 1.0 - self.radius {
-            self.velocity_y = -self.velocity_y * 0.8; // Dampening factor
+            self.velocity_y = -self.velocity_y * damping;
             
             // Ensure particle stays within bounds
             if self.y < self.radius {
@@ -74,14 +246,28 @@ impl Particle {
                 self.y = 1.0 - self.radius;
             }
         }
-        
-        if self.lifetime > 0 {
-            self.lifetime -= 1;
-        }
     }
     
     pub fn is_alive(&self) -> bool {
-        self.lifetime > 0
+        self.lifetime > 0.0
+    }
+    
+    // Start `distance` further along the direction of travel
+    fn launch(mut self, distance: f32) -> Self {
+        let speed = self.velocity_x.hypot(self.velocity_y).max(f32::EPSILON);
+        self.x += self.velocity_x / speed * distance;
+        self.y += self.velocity_y / speed * distance;
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+        self
+    }
+    
+    // Where to draw the particle, `alpha` of the way from the previous step to the current one
+    pub fn render_position(&self, alpha: f32) -> (f32, f32) {
+        (
+            self.prev_x + (self.x - self.prev_x) * alpha,
+            self.prev_y + (self.y - self.prev_y) * alpha,
+        )
     }
     
     pub fn collides_with(&self, other: &Particle) -> bool {
@@ -91,53 +277,194 @@ impl Particle {
         distance_squared < (self.radius + other.radius).powi(2)
     }
     
-    // Create a new particle from a collision
-    pub fn spawn_from_collision(p1: &Particle, p2: &Particle, rng: &mut impl Rng) -> Self {
-        // New particle spawns at the midpoint of collision
-        let mid_x = (p1.x + p2.x) / 2.0;
-        let mid_y = (p1.y + p2.y) / 2.0;
+    // Resolve an elastic collision with `other`, using energy as mass. Both
+    // momentum and kinetic energy are conserved, and overlapping particles are
+    // pushed apart. Returns false if the pair is already separating, which is
+    // how a lingering overlap avoids being counted as a new collision.
+    pub fn collide(&mut self, other: &mut Particle) -> bool {
+        let dx = other.x - self.x;
+        let dy = other.y - self.y;
+        let distance = (dx * dx + dy * dy).sqrt();
         
-        // Random angle for velocity
-        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        // Collision normal, from self towards other
+        let (nx, ny) = if distance > f32::EPSILON {
+            (dx / distance, dy / distance)
+        } else {
+            (1.0, 0.0)
+        };
         
-        // Combined energy creates more energetic new particles
-        let combined_energy = (p1.energy + p2.energy) * 0.6;
-        let speed = 0.05 * FRAME_RATE_FACTOR * combined_energy;
+        let m1 = self.energy.max(0.01);
+        let m2 = other.energy.max(0.01);
         
-        // New lifetime is average of parents
-        let lifetime = ((p1.lifetime as u16 + p2.lifetime as u16) / 3) as u8;
+        // Separate the pair, each moving in proportion to the other's mass
+        let overlap = (self.radius + other.radius - distance).max(0.0);
+        let total_mass = m1 + m2;
+        self.x -= nx * overlap * (m2 / total_mass);
+        self.y -= ny * overlap * (m2 / total_mass);
+        other.x += nx * overlap * (m1 / total_mass);
+        other.y += ny * overlap * (m1 / total_mass);
         
-        Particle {
-            x: mid_x,
-            y: mid_y,
-            velocity_x: angle.cos() * speed,
-            velocity_y: angle.sin() * speed,
-            lifetime,
-            radius: COLLISION_RADIUS,
-            energy: combined_energy,
+        // Closing speed along the normal; not approaching means no impact
+        let closing_speed = (self.velocity_x - other.velocity_x) * nx + (self.velocity_y - other.velocity_y) * ny;
+        if closing_speed <= 0.0 {
+            return false;
+        }
+        
+        let impulse = 2.0 * closing_speed / (1.0 / m1 + 1.0 / m2);
+        self.velocity_x -= impulse / m1 * nx;
+        self.velocity_y -= impulse / m1 * ny;
+        other.velocity_x += impulse / m2 * nx;
+        other.velocity_y += impulse / m2 * ny;
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReactorPhase {
+    // Rods follow the load
+    Operating,
+    // The core has blown; the explosion animation is playing
+    Meltdown,
+    // Rods fully inserted (after a SCRAM or a meltdown) while the core cools;
+    // operation resumes automatically once it is cold
+    Cooldown,
+}
+
+// When the core melts down: the collision rate, averaged over a sliding
+// window, is above `collision_rate` while stability has stayed above
+// `instability` for at least `sustain` seconds
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MeltdownCriteria {
+    pub window: f32,          // seconds
+    pub collision_rate: f32,  // collisions per second
+    pub instability: f32,     // stability score, 0-100
+    pub sustain: f32,         // seconds
+}
+
+impl Default for MeltdownCriteria {
+    fn default() -> Self {
+        MeltdownCriteria {
+            window: 5.0,
+            collision_rate: 60.0,
+            instability: 80.0,
+            sustain: 2.0,
         }
     }
 }
 
+// Everything the reactor reacts to during an update
+#[derive(Clone, Debug, Default)]
+pub struct ReactorInput {
+    // Average CPU load, 0-100%
+    pub cpu_load: f32,
+    // Per-core load, 0-100%; each core drives its own control rod
+    pub core_loads: Vec<f32>,
+    // Measured CPU temperature in °C, if a sensor is available
+    pub measured_temperature: Option<f32>,
+    // Memory usage, 0-100%, and disk throughput in bytes/s; one of them drives
+    // the coolant pumps. Disk throughput also emits alphas.
+    pub memory_usage: f32,
+    pub disk_io_rate: f32,
+    // Kernel events per second, emitting betas and gammas
+    pub context_switch_rate: f32,
+    pub interrupt_rate: f32,
+    pub page_fault_rate: f32,
+    // Major faults and pages swapped in and out per second, and the 1-minute
+    // load average; they make the core unstable
+    pub major_fault_rate: f32,
+    pub swap_rate: f32,
+    pub load_average: f32,
+    // Network throughput in bytes/s, which drives the turbine
+    pub network_rate: f32,
+    // Utilization of the busiest disk and usage of the fullest filesystem,
+    // 0.0 - 1.0: the first pressurises the coolant loop, the second drains
+    // its reservoir
+    pub disk_saturation: f32,
+    pub filesystem_usage: f32,
+    // Time stalled on the most contended resource, in %, if the kernel
+    // reports it; it sets the coolant loop's pressure
+    pub stall: Option<f32>,
+    // Processes the OOM killer has taken, in cgroup mode; any rise in it is
+    // a meltdown
+    pub oom_kills: Option<u64>,
+}
+
+impl ReactorInput {
+    // How hard the machine is thrashing, 0.0 - 1.0
+    pub fn thrashing(&self) -> f32 {
+        let swapping = self.swap_rate / SWAP_FULL_SCALE;
+        let faulting = self.major_fault_rate / MAJOR_FAULT_FULL_SCALE;
+        swapping.max(faulting).clamp(0.0, 1.0)
+    }
+    
+    // How far the run queue outgrows the cores, 0.0 - 1.0
+    pub fn overload(&self) -> f32 {
+        let cores = self.core_loads.len().max(1) as f32;
+        (self.load_average / cores - 1.0).clamp(0.0, 1.0)
+    }
+}
+
 pub struct Reactor {
     pub radiation_level: f32,
     pub core_temperature: f32,
     pub pressure: f32,
-    pub rod_position: f32,  // 0.0 = fully inserted (low power), 1.0 = fully withdrawn (high power)
+    pub rod_position: f32,  // 0.0 = fully inserted (low power), 1.0 = fully withdrawn (high power), averaged over all rods
+    pub rods: Vec<f32>,     // Individual rod positions, one per CPU core
+    // Manual or automatic control; the rods follow the load when there is neither
+    pub operator: Option<OperatorControls>,
+    pub controller: Option<PidController>,
     pub instability: f32,
     pub history: VecDeque<f32>,
     pub particles: Vec<Particle>,
-    pub rods_count: u8,
     pub coolant_level: f32,
-    update_counter: u32,
+    pub coolant: CoolantLoop,
+    pub turbine: Turbine,
+    // Fraction of nominal power and reactivity ($), as reported by the model
+    pub power: f32,
+    pub reactivity: f32,
+    // Decay heat and xenon, built up from the CPU-seconds the core has burnt
+    pub products: FissionProducts,
+    pub fuel: FuelLattice,
+    model: Box<dyn ReactorModel>,
+    // Unsimulated time carried over to the next update, always < FIXED_TIMESTEP
+    accumulator: f32,
+    history_timer: f32,
+    explosion_timer: f32,
     collisions_this_frame: usize,
+    // Collision broad phase, and its scratch list of candidate pairs
+    grid: SpatialGrid,
+    pairs: Vec<(usize, usize)>,
+    // Collisions over the whole session
     pub total_collisions: usize,
-    pub is_exploding: bool,
+    pub meltdown: MeltdownCriteria,
+    // Collisions in each step of the meltdown window, oldest first, and their sum
+    collision_window: VecDeque<usize>,
+    window_collisions: usize,
+    // Time spent above the meltdown stability threshold, drained while below
+    critical_time: f32,
+    // OOM kills last seen, so only new ones count
+    oom_kills: Option<u64>,
+    pub phase: ReactorPhase,
+    phase_time: f32,
     pub explosion_frame: u8,
+    // Session statistics, kept across restarts
+    pub meltdowns: u32,
+    pub scrams: u32,
+    pub refuels: u32,
+    pub temperature_source: TemperatureSource,
+    measured_temperature: Option<f32>,
+    // Every random decision comes from this generator, so a seed and a load
+    // trace fully determine a run
+    seed: u64,
+    rng: StdRng,
 }
 
 impl Reactor {
     pub fn new() -> Self {
+        Self::with_seed(rand::rng().random())
+    }
+    
+    pub fn with_seed(seed: u64) -> Self {
         Reactor {
             radiation_level: 10.0,
             core_temperature: 220.0,
@@ -146,183 +473,623 @@ impl Reactor {
             instability: 0.0,
             history: VecDeque::with_capacity(HISTORY_SIZE),
             particles: Vec::with_capacity(MAX_PARTICLES),
-            rods_count: 20,
+            rods: vec![0.3],
+            operator: None,
+            controller: None,
             coolant_level: 95.0,
-            update_counter: 0,
+            coolant: CoolantLoop::default(),
+            turbine: Turbine::new(),
+            power: 0.09,
+            reactivity: 0.0,
+            products: FissionProducts::new(),
+            fuel: FuelLattice::new(),
+            model: ModelKind::default().build(),
+            accumulator: 0.0,
+            history_timer: 0.0,
+            explosion_timer: 0.0,
             collisions_this_frame: 0,
+            grid: SpatialGrid::new(2.0 * COLLISION_RADIUS),
+            pairs: Vec::new(),
             total_collisions: 0,
-            is_exploding: false,
+            meltdown: MeltdownCriteria::default(),
+            collision_window: VecDeque::new(),
+            window_collisions: 0,
+            critical_time: 0.0,
+            oom_kills: None,
+            phase: ReactorPhase::Operating,
+            phase_time: 0.0,
             explosion_frame: 0,
+            meltdowns: 0,
+            scrams: 0,
+            refuels: 0,
+            temperature_source: TemperatureSource::Model,
+            measured_temperature: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
         }
     }
     
-    pub fn update(&mut self, cpu_load: f32) {
-        self.update_counter = (self.update_counter + 1) % 1_000_000;
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    
+    // Advance the simulation by `dt` seconds of real time, in fixed steps
+    pub fn update(&mut self, dt: f32, input: &ReactorInput) {
+        for _ in 0..self.due_steps(dt, 1.0) {
+            self.step(input);
+        }
+    }
+    
+    // Start a frame of `dt` seconds of real time, running `speed` times faster
+    // than real time, and return how many fixed steps are now due. The caller
+    // runs them with `step`, which lets it feed each one a fresh input.
+    pub fn due_steps(&mut self, dt: f32, speed: f32) -> usize {
         self.collisions_this_frame = 0;
+        // Clamp the real frame time, so a fast replay isn't cut short
+        self.accumulator += clamp(dt, 0.0, MAX_FRAME_TIME) * speed.max(0.0);
         
-        // If reactor is exploding, advance explosion animation and skip normal updates
-        if self.is_exploding {
-            if self.update_counter % 5 == 0 { // Slow down animation
-                if self.explosion_frame < 10 {
-                    self.explosion_frame += 1;
-                }
-            }
-            return;
+        let mut steps = 0;
+        while self.accumulator >= FIXED_TIMESTEP {
+            self.accumulator -= FIXED_TIMESTEP;
+            steps += 1;
         }
+        steps
+    }
+    
+    // How far rendering is between the last step and the next one (0.0-1.0)
+    pub fn interpolation(&self) -> f32 {
+        self.accumulator / FIXED_TIMESTEP
+    }
+    
+    // Advance the simulation by exactly one FIXED_TIMESTEP
+    pub fn step(&mut self, input: &ReactorInput) {
+        let dt = FIXED_TIMESTEP;
+        let cpu_load = input.cpu_load.clamp(0.0, 100.0);
+        self.measured_temperature = input.measured_temperature;
         
-        // Update reactor parameters based on CPU load
-        let target_rod_position = cpu_load / 100.0;
-        
-        // Simulate control rod movement (they move slowly)
-        if (self.rod_position - target_rod_position).abs() > 0.01 {
-            // Scale movement for higher frame rate
-            self.rod_position += (target_rod_position - self.rod_position) * 0.03;
+        // Work done keeps counting whatever the core is doing
+        if input.core_loads.is_empty() {
+            self.products.update(&[cpu_load], dt);
+        } else {
+            self.products.update(&input.core_loads, dt);
         }
         
-        // Update reactor core temperature
-        let target_temp = 220.0 + (700.0 * self.rod_position);
-        // Scale temperature change for higher frame rate
-        self.core_temperature += (target_temp - self.core_temperature) * 0.03;
-        
-        // Update radiation level
-        self.radiation_level = clamp(10.0 + (90.0 * self.rod_position * self.rod_position), 10.0, 100.0);
+        self.phase_time += dt;
         
-        // Pressure increases with temperature
-        self.pressure = clamp(101.3 + (self.core_temperature - 220.0) * 0.1, 100.0, 300.0);
+        // The OOM killer striking melts the core down on the spot, whatever
+        // else it is doing
+        if let Some(kills) = input.oom_kills {
+            let killed = self.oom_kills.is_some_and(|seen| kills > seen);
+            self.oom_kills = Some(kills);
+            if killed && self.phase == ReactorPhase::Operating {
+                self.melt_down();
+            }
+        }
         
-        // Coolant is more depleted at higher temperatures
-        self.coolant_level = clamp(100.0 - (self.core_temperature - 220.0) * 0.05, 0.0, 100.0);
+        match self.phase {
+            ReactorPhase::Operating => self.operate(input, cpu_load, dt),
+            ReactorPhase::Meltdown => self.advance_meltdown(dt),
+            ReactorPhase::Cooldown => self.cool_down(input, dt),
+        }
         
-        // Calculate instability (random fluctuations that increase with load)
-        let mut rng = rand::thread_rng();
-        let random_factor = rng.gen_range(-5.0..5.0);
-        self.instability = (self.rod_position * 30.0) + random_factor;
+        // The turbine trips off the line whenever the core isn't operating
+        self.turbine.update(input.network_rate, self.phase != ReactorPhase::Operating, dt);
         
-        // Update history for graphs - update at original rate, not every frame
-        if self.update_counter % 3 == 0 {
+        // Update history for graphs at a fixed rate in every phase, so the
+        // whole session stays on the chart
+        self.history_timer += dt;
+        if self.history_timer >= HISTORY_INTERVAL {
+            self.history_timer -= HISTORY_INTERVAL;
             if self.history.len() >= HISTORY_SIZE {
                 self.history.pop_front();
             }
             self.history.push_back(self.core_temperature);
         }
+    }
+    
+    fn operate(&mut self, input: &ReactorInput, cpu_load: f32, dt: f32) {
+        // Update reactor parameters based on CPU load - one rod per core
+        self.update_rods(input, cpu_load, dt);
+        
+        // The model turns rods and load into core temperature and power. Under
+        // manual or automatic control the load no longer moves the rods, so it
+        // pushes the core as if they were further out. The operator's
+        // difficulty scales every effect the load has.
+        let load = clamp(cpu_load / 100.0, 0.0, 1.0);
+        let rod_position = clamp(self.rod_position + self.load_disturbance(load), 0.0, 1.0);
+        let load_gain = self.operator.as_ref().map_or(1.0, |operator| operator.load_gain);
+        let output = self.model.step(&ModelInput {
+            rod_position,
+            cpu_load: load * load_gain,
+            core_temperature: self.core_temperature,
+            measured_temperature: self.measured_core_temperature(),
+            decay_heat: self.products.decay_heat(),
+            xenon: self.products.xenon(),
+            fuel: self.fuel.fuel_remaining(),
+        }, dt);
+        self.core_temperature = output.core_temperature;
+        self.power = output.power;
+        self.reactivity = output.reactivity;
+        self.update_coolant(input, dt);
+        self.update_derived();
+        
+        // Calculate instability (random fluctuations that increase with load).
+        // Thrashing shakes the core, and makes it jumpier, as does a backed-up
+        // run queue, even with the CPU percentage looking fine.
+        let thrashing = input.thrashing();
+        let random_factor = self.rng.random_range(-5.0..5.0) * (1.0 + 2.0 * thrashing);
+        let disturbance = thrashing * THRASH_INSTABILITY + input.overload() * OVERLOAD_INSTABILITY;
+        self.instability = output.instability + disturbance + random_factor;
         
         // Update existing particles
         self.particles.retain(|p| p.is_alive());
         for particle in &mut self.particles {
-            particle.update();
+            particle.update(dt);
         }
         
-        // Check for collisions - we use indices to avoid borrow checker issues
-        let mut new_particles = Vec::new();
+        // Fuel burns up with the work done, whether or not it is split
+        let cpu_factor = clamp(cpu_load / 100.0, 0.0, 1.0);
+        self.fuel.update(cpu_factor * dt, dt);
         
-        // We'll only check collision every few frames for performance
-        if self.update_counter % 2 == 0 && self.particles.len() > 5 {
-            for i in 0..self.particles.len() {
-                for j in (i+1)..self.particles.len() {
-                    if self.particles[i].collides_with(&self.particles[j]) {
-                        // Collision detected! 
-                        self.collisions_this_frame += 1;
-                        
-                        // Safety check for total_collisions to prevent overflow
-                        if self.total_collisions < usize::MAX - 1 {
-                            self.total_collisions += 1;
-                        }
-                        
-                        // Each collision increases core temperature slightly
-                        self.core_temperature = (self.core_temperature + 0.5).min(1000.0);
-                        
-                        // Ensure radiation level stays within bounds
-                        self.radiation_level = clamp(self.radiation_level, 10.0, 100.0);
-                        
-                        // Ensure coolant level stays within bounds
-                        self.coolant_level = clamp(self.coolant_level, 0.0, 100.0);
-                        
-                        // Particles bounce off each other
-                        if let Some(p1) = self.particles.get_mut(i) {
-                            p1.velocity_x = -p1.velocity_x;
-                            p1.velocity_y = -p1.velocity_y;
-                        }
-                        
-                        if let Some(p2) = self.particles.get_mut(j) {
-                            p2.velocity_x = -p2.velocity_x;
-                            p2.velocity_y = -p2.velocity_y;
-                        }
-                        
-                        // Only create new particles if we're not at capacity and chance permits
-                        let cpu_factor = clamp(cpu_load / 100.0, 0.0, 1.0);
-                        let collision_chance = 0.3 + (cpu_factor * 0.4); // 30-70% chance
-                        
-                        if rng.r#gen::<f32>() < collision_chance && 
-                            self.particles.len() + new_particles.len() < MAX_PARTICLES {
-                            // Spawn 1-3 new particles from the collision
-                            let spawn_count = ((cpu_factor * 3.0) as usize).min(3).max(1);
-                            
-                            for _ in 0..spawn_count {
-                                if let (Some(p1), Some(p2)) = (self.particles.get(i), self.particles.get(j)) {
-                                    new_particles.push(Particle::spawn_from_collision(p1, p2, &mut rng));
-                                }
-                            }
-                        }
-                    }
+        // A neutron that hits a fuel pellet is absorbed, and splits it into
+        // more neutrons unless it is captured. Inserted rods capture more of
+        // them, and a pellet needs time to recharge, so a chain reaction
+        // spreads over the lattice.
+        let intensity = self.rod_position * cpu_factor;
+        let capacity = MAX_PARTICLES.saturating_sub(self.particles.len());
+        let mut new_particles = Vec::new();
+        for particle in &mut self.particles {
+            if particle.species != Species::Neutron || !particle.is_alive() {
+                continue;
+            }
+            let Some(index) = self.fuel.pellet_at(particle.x, particle.y) else {
+                continue;
+            };
+            let pellet = self.fuel.pellets[index];
+            if pellet.is_flashing() {
+                continue;
+            }
+            particle.lifetime = 0.0;
+            if pellet.is_spent() || self.rng.random::<f32>() >= pellet.fuel * self.rod_position {
+                continue;
+            }
+            
+            self.fuel.split(index);
+            self.core_temperature = (self.core_temperature + FISSION_HEAT).min(1000.0);
+            for _ in 0..self.rng.random_range(FISSION_NEUTRONS) {
+                if new_particles.len() < capacity {
+                    new_particles.push(Particle::new(pellet.x, pellet.y, intensity, &mut self.rng));
                 }
             }
         }
-        
-        // Add newly created particles from collisions
         self.particles.extend(new_particles);
         
+        // Check for collisions. The grid narrows the search down to particles
+        // in neighbouring cells, so this stays cheap with thousands of them.
+        let mut step_collisions = 0;
+        
+        if self.particles.len() > 5 {
+            self.grid.rebuild(self.particles.iter().map(|p| (p.x, p.y)));
+            self.grid.candidate_pairs(&mut self.pairs);
+            
+            for &(i, j) in &self.pairs {
+                // Everything interacts with neutrons, but the other species
+                // pass through each other
+                let neutron = self.particles[i].species == Species::Neutron
+                    || self.particles[j].species == Species::Neutron;
+                if !neutron || !self.particles[i].collides_with(&self.particles[j]) {
+                    continue;
+                }
+                
+                // Particles bounce off each other; a pair that is already
+                // moving apart is still overlapping from an earlier impact
+                let (head, tail) = self.particles.split_at_mut(j);
+                if !head[i].collide(&mut tail[0]) {
+                    continue;
+                }
+                
+                // Collision detected! 
+                self.collisions_this_frame += 1;
+                step_collisions += 1;
+                
+                // Safety check for total_collisions to prevent overflow
+                self.total_collisions = self.total_collisions.saturating_add(1);
+                
+                // Each collision increases core temperature slightly
+                self.core_temperature = (self.core_temperature + 0.5).min(1000.0);
+                
+                // Ensure radiation level stays within bounds
+                self.radiation_level = clamp(self.radiation_level, 10.0, 100.0);
+                
+                // Ensure coolant level stays within bounds
+                self.coolant_level = clamp(self.coolant_level, 0.0, 100.0);
+            }
+        }
+        
         // Generate new particles based on radiation level and CPU load
-        let cpu_factor = cpu_load / 100.0;
-        let intensity = self.rod_position * cpu_factor;
         
-        // Increase particle chance based on both radiation level and CPU load
-        // Adjust particle generation rate for higher frame rate
-        let particle_chance = ((self.radiation_level / 100.0) * 0.27) * (1.0 + cpu_factor);
+        // Increase particle rate (per emitter, per second) based on both radiation level and CPU load
+        let particle_rate = ((self.radiation_level / 100.0) * 8.0) * (1.0 + cpu_factor);
+        let particle_chance = particle_rate * dt;
         
         // Dynamic max particles based on CPU load
         let dynamic_max = (MAX_PARTICLES as f32 * (0.3 + 0.7 * cpu_factor)) as usize;
         
-        // Generate multiple particles per frame at high load
+        // Use more emitters at high load
         let particles_per_update = (1 + (cpu_factor * 3.0) as usize).min(4);
         
         for _ in 0..particles_per_update {
-            if rng.r#gen::<f32>() < particle_chance && self.particles.len() < dynamic_max {
-                // Generate particles near the core with more variation at higher loads
-                let core_x = 0.5;
-                let core_y = 0.5;
-                let spread = 0.1 + (cpu_factor * 0.1);
-                let offset_x = rng.gen_range(-spread..spread);
-                let offset_y = rng.gen_range(-spread..spread);
-                
-                self.particles.push(Particle::new(
-                    core_x + offset_x, 
-                    core_y + offset_y,
-                    intensity
-                ));
+            if self.rng.random::<f32>() < particle_chance && self.particles.len() < dynamic_max {
+                // Spontaneous fission in a pellet that still has fuel; a spent
+                // core emits no neutrons. The neutron starts clear of the
+                // pellet, which isn't flashing and would absorb it straight away.
+                let Some(&pellet) = self.fuel.random_fuelled(&mut self.rng) else {
+                    break;
+                };
+                let neutron = Particle::new(pellet.x, pellet.y, intensity, &mut self.rng);
+                self.particles.push(neutron.launch(PELLET_RADIUS * SPAWN_CLEARANCE));
+            }
+        }
+        
+        // The other species come from the rest of the machine's activity
+        for (species, level) in [
+            (Species::Alpha, input.disk_io_rate / ALPHA_FULL_SCALE),
+            (Species::Beta, (input.context_switch_rate / BETA_FULL_SCALE).max(input.interrupt_rate / INTERRUPT_FULL_SCALE)),
+            (Species::Gamma, input.page_fault_rate / GAMMA_FULL_SCALE),
+        ] {
+            let level = clamp(level, 0.0, 1.0);
+            if self.rng.random::<f32>() < level * SPECIES_EMISSION_RATE * dt && self.particles.len() < dynamic_max {
+                let offset_x = self.rng.random_range(-0.1..0.1);
+                let offset_y = self.rng.random_range(-0.1..0.1);
+                self.particles.push(Particle::of_species(species, 0.5 + offset_x, 0.5 + offset_y, level, &mut self.rng));
             }
         }
         
-        // Check if we've reached critical mass for explosion
-        if self.total_collisions > 100 && self.stability() > 80.0 {
-            self.is_exploding = true;
+        self.record_collisions(step_collisions);
+        
+        // Melt down once the collision rate is critical and the core has been
+        // unstable for long enough - a brief spike is not enough. Unstable
+        // time drains away rather than resetting, so noise around the
+        // threshold doesn't restart the clock.
+        if self.stability() > self.meltdown.instability {
+            self.critical_time += dt;
+        } else {
+            self.critical_time = (self.critical_time - dt).max(0.0);
+        }
+        if self.collision_rate() >= self.meltdown.collision_rate
+            && self.critical_time >= self.meltdown.sustain
+        {
+            self.melt_down();
+        }
+    }
+    
+    fn melt_down(&mut self) {
+        self.set_phase(ReactorPhase::Meltdown);
+        self.explosion_frame = 0;
+        self.explosion_timer = 0.0;
+        self.meltdowns += 1;
+    }
+    
+    // Advance the explosion animation, then make the core safe
+    fn advance_meltdown(&mut self, dt: f32) {
+        self.record_collisions(0);
+        self.explosion_timer += dt;
+        if self.explosion_timer >= EXPLOSION_FRAME_TIME {
+            self.explosion_timer -= EXPLOSION_FRAME_TIME;
+            self.explosion_frame = (self.explosion_frame + 1).min(10);
+        }
+        
+        if self.phase_time >= MELTDOWN_DURATION {
+            // What's left of the core is replaced before it cools
+            self.model.reset();
+            self.products.reset();
+            self.fuel.refuel();
+            self.particles.clear();
             self.explosion_frame = 0;
+            self.set_phase(ReactorPhase::Cooldown);
+        }
+    }
+    
+    // Rods drop all the way in and the core cools, whatever the load
+    fn cool_down(&mut self, input: &ReactorInput, dt: f32) {
+        self.record_collisions(0);
+        for rod in &mut self.rods {
+            *rod = approach(*rod, 0.0, SCRAM_ROD_SPEED, dt);
+        }
+        self.rod_position = self.rods.iter().sum::<f32>() / self.rods.len() as f32;
+        if let Some(controller) = &mut self.controller {
+            controller.track(self.rod_position, Some(self.core_temperature));
+        }
+        
+        // The model still sees the falling rods, so its power decays as it
+        // would, but the temperature is pulled down regardless
+        let output = self.model.step(&ModelInput {
+            rod_position: self.rod_position,
+            cpu_load: 0.0,
+            core_temperature: self.core_temperature,
+            measured_temperature: None,
+            decay_heat: self.products.decay_heat(),
+            xenon: self.products.xenon(),
+            fuel: self.fuel.fuel_remaining(),
+        }, dt);
+        self.power = output.power;
+        self.reactivity = output.reactivity;
+        
+        // The pumps keep running, but emergency cooling sets the core
+        // temperature; only decay heat holds it up
+        let decay_heat = self.products.decay_heat();
+        let target_flow = self.pump_target(input);
+        self.coolant.step(self.core_temperature, self.power + decay_heat, target_flow, dt);
+        let target = 220.0 + DECAY_HEAT_RISE * decay_heat;
+        self.core_temperature = approach(self.core_temperature, target, COOLDOWN_SPEED, dt);
+        self.update_derived();
+        self.instability = output.instability;
+        
+        // Remaining particles fly out and decay, but nothing new is emitted
+        self.particles.retain(|p| p.is_alive());
+        for particle in &mut self.particles {
+            particle.update(dt);
+        }
+        
+        if self.phase_time >= COOLDOWN_MIN_TIME && self.core_temperature < COOLDOWN_TEMPERATURE {
+            self.reset_meltdown_window();
+            self.set_phase(ReactorPhase::Operating);
+        }
+    }
+    
+    fn window_steps(&self) -> usize {
+        ((self.meltdown.window / FIXED_TIMESTEP).round() as usize).max(1)
+    }
+    
+    fn record_collisions(&mut self, collisions: usize) {
+        self.collision_window.push_back(collisions);
+        self.window_collisions += collisions;
+        // The window may also have been shortened since the last step
+        while self.collision_window.len() > self.window_steps() {
+            if let Some(oldest) = self.collision_window.pop_front() {
+                self.window_collisions -= oldest;
+            }
+        }
+    }
+    
+    fn reset_meltdown_window(&mut self) {
+        self.collision_window.clear();
+        self.window_collisions = 0;
+        self.critical_time = 0.0;
+    }
+    
+    // Collisions per second, averaged over the meltdown window
+    pub fn collision_rate(&self) -> f32 {
+        self.window_collisions as f32 / self.meltdown.window.max(FIXED_TIMESTEP)
+    }
+    
+    // Estimated seconds until meltdown if the recent collision rate (over the
+    // configured share of the meltdown window) and the current instability
+    // carry on, or None if the core is not heading for one
+    pub fn time_to_criticality(&self) -> Option<f32> {
+        if self.phase != ReactorPhase::Operating || self.stability() <= self.meltdown.instability {
+            return None;
+        }
+        let sustain_left = (self.meltdown.sustain - self.critical_time).max(0.0);
+        
+        let capacity = self.window_steps();
+        let target = self.meltdown.collision_rate * capacity as f32 * FIXED_TIMESTEP;
+        let mut sum = self.window_collisions as f32;
+        if sum >= target {
+            return Some(sustain_left);
+        }
+        
+        let recent_steps = ((capacity as f32 * RECENT_RATE_SHARE).round() as usize)
+            .max(1)
+            .min(self.collision_window.len());
+        if recent_steps == 0 {
+            return None;
+        }
+        let per_step = self.collision_window.iter().rev().take(recent_steps).sum::<usize>() as f32
+            / recent_steps as f32;
+        
+        // Play the window forward: each new step adds the recent rate and,
+        // once the window is full, pushes out its oldest step. After a whole
+        // window the sum is just the recent rate, so give up there.
+        let mut oldest = self.collision_window.iter();
+        let mut len = self.collision_window.len();
+        for step in 1..=capacity {
+            let dropped = if len >= capacity {
+                oldest.next().copied().unwrap_or(0) as f32
+            } else {
+                len += 1;
+                0.0
+            };
+            sum += per_step - dropped;
+            if sum >= target {
+                return Some((step as f32 * FIXED_TIMESTEP).max(sustain_left));
+            }
+        }
+        None
+    }
+    
+    fn set_phase(&mut self, phase: ReactorPhase) {
+        self.phase = phase;
+        self.phase_time = 0.0;
+    }
+    
+    // Emergency shutdown: drop every rod and cool the core before resuming
+    pub fn scram(&mut self) {
+        if self.phase == ReactorPhase::Operating {
+            self.scrams += 1;
+            self.set_phase(ReactorPhase::Cooldown);
+        }
+    }
+    
+    // Swap spent pellets for fresh fuel; not while the core is blowing up
+    pub fn refuel(&mut self) {
+        if self.phase != ReactorPhase::Meltdown {
+            self.fuel.refuel();
+            self.refuels += 1;
+        }
+    }
+    
+    // Put a fresh core into operation straight away. Session history and
+    // statistics are kept.
+    pub fn restart(&mut self) {
+        self.model.reset();
+        self.products.reset();
+        self.fuel.refuel();
+        self.coolant = CoolantLoop::new(self.coolant.drive);
+        self.particles.clear();
+        self.core_temperature = 220.0;
+        self.instability = 0.0;
+        self.explosion_frame = 0;
+        self.explosion_timer = 0.0;
+        self.reset_meltdown_window();
+        self.update_derived();
+        self.set_phase(ReactorPhase::Operating);
+    }
+    
+    pub fn model_kind(&self) -> ModelKind {
+        self.model.kind()
+    }
+    
+    // Swap the physics model at runtime; the new one starts from a fresh
+    // core, but the reactor's own temperature carries over
+    pub fn set_model(&mut self, kind: ModelKind) {
+        if kind != self.model.kind() {
+            self.model = kind.build();
         }
     }
     
+    pub fn model_details(&self) -> Vec<String> {
+        self.model.details()
+    }
+    
+    // How many particles of each species are alive, in `Species::ALL` order
+    pub fn species_counts(&self) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for particle in &self.particles {
+            counts[particle.species as usize] += 1;
+        }
+        counts
+    }
+    
+    pub fn is_exploding(&self) -> bool {
+        self.phase == ReactorPhase::Meltdown
+    }
+    
+    // How much further out the load effectively pulls the rods, for a load
+    // of 0.0 - 1.0; nothing when the rods follow it
+    pub fn load_disturbance(&self, load: f32) -> f32 {
+        match (&self.operator, &self.controller) {
+            (Some(operator), _) => operator.disturbance(load),
+            (None, Some(_)) => LOAD_DISTURBANCE * load,
+            (None, None) => 0.0,
+        }
+    }
+    
+    // Flow the pumps spool towards: whatever memory or disk leave them, cut
+    // back by the operator if there is one
+    fn pump_target(&self, input: &ReactorInput) -> f32 {
+        let available = self.coolant.target_flow(input.memory_usage, input.disk_io_rate);
+        match &self.operator {
+            Some(operator) => available * operator.pump_demand,
+            None => available,
+        }
+    }
+    
+    // Pumps carry heat out of the core; starved pumps leave it in
+    fn update_coolant(&mut self, input: &ReactorInput, dt: f32) {
+        let target_flow = self.pump_target(input);
+        let heat = self.power + self.products.decay_heat();
+        self.coolant.update_storage(input.disk_saturation, input.filesystem_usage, dt);
+        self.coolant.update_contention(input.stall);
+        let core_temperature = self.coolant.step(self.core_temperature, heat, target_flow, dt);
+        self.core_temperature = core_temperature.min(1000.0);
+    }
+    
+    // Radiation follows power; pressure and coolant come from the coolant loop
+    fn update_derived(&mut self) {
+        // Update radiation level
+        self.radiation_level = clamp(10.0 + (90.0 * self.power), 10.0, 100.0);
+        
+        self.pressure = self.coolant.pressure;
+        self.coolant_level = self.coolant.coolant_level;
+    }
+    
+    fn update_rods(&mut self, input: &ReactorInput, cpu_load: f32, dt: f32) {
+        let single = [cpu_load];
+        let loads: &[f32] = if input.core_loads.is_empty() {
+            &single
+        } else {
+            &input.core_loads
+        };
+        
+        // A new core count (first sample, or a hotplugged CPU) starts from the current average
+        if self.rods.len() != loads.len() {
+            self.rods.resize(loads.len(), self.rod_position);
+        }
+        
+        // The controller drives every rod together, at its own rate limit
+        if self.operator.is_none()
+            && let Some(controller) = &mut self.controller
+        {
+            let output = controller.update(self.core_temperature, dt);
+            self.rods.fill(output);
+            self.rod_position = output;
+            return;
+        }
+        
+        let rod_count = self.rods.len();
+        for (i, (rod, load)) in self.rods.iter_mut().zip(loads).enumerate() {
+            let target_rod_position = match &self.operator {
+                Some(operator) => operator.banks[OperatorControls::bank_of(i, rod_count)],
+                None => clamp(load / 100.0, 0.0, 1.0),
+            };
+            
+            // Simulate control rod movement (they move slowly)
+            if (*rod - target_rod_position).abs() > 0.01 {
+                *rod = approach(*rod, target_rod_position, ROD_SPEED, dt);
+            }
+        }
+        
+        self.rod_position = self.rods.iter().sum::<f32>() / self.rods.len() as f32;
+    }
+    
     pub fn stability(&self) -> f32 {
         // Calculate a stability score (0-100) where higher means more unstable
         let temp_factor = (self.core_temperature - 220.0) / 700.0;
         let rad_factor = self.radiation_level / 100.0;
         let coolant_factor = (100.0 - self.coolant_level) / 100.0;
         
-        (temp_factor * 40.0 + rad_factor * 40.0 + self.instability + coolant_factor * 20.0).max(0.0).min(100.0)
+        (temp_factor * 40.0 + rad_factor * 40.0 + self.instability + coolant_factor * 20.0).clamp(0.0, 100.0)
+    }
+
+    // Core temperature implied by the hardware sensor, if we are driven by it
+    fn measured_core_temperature(&self) -> Option<f32> {
+        if self.temperature_source != TemperatureSource::Measured {
+            return None;
+        }
+        self.measured_temperature.map(|celsius| {
+            let fraction = (celsius - MEASURED_IDLE_CELSIUS) / (MEASURED_HOT_CELSIUS - MEASURED_IDLE_CELSIUS);
+            220.0 + 700.0 * clamp(fraction, 0.0, 1.0)
+        })
     }
     
     pub fn collisions(&self) -> usize {
         self.collisions_this_frame
     }
+    
+}
+
+impl Default for Reactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Frame-rate independent easing: close the gap to `target` at `rate` per second
+pub(crate) fn approach(current: f32, target: f32, rate: f32, dt: f32) -> f32 {
+    current + (target - current) * (1.0 - (-rate * dt).exp())
 }
 
 fn clamp(value: f32, min: f32, max: f32) -> f32 {
-    value.max(min).min(max)
-} 
\ No newline at end of file
+    value.clamp(min, max)
+}
@@ -0,0 +1,260 @@
+use crate::app::{Action, AppConfig};
+use crate::metrics::{MetricsSource, Playback, MAX_PLAYBACK_SPEED, MIN_PLAYBACK_SPEED};
+use crate::reactor::FIXED_TIMESTEP;
+use crate::system::SystemInfo;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// A recording is a JSONL file: the settings the session ran with, then every
+// snapshot and operator action tagged with the simulation step it came just
+// before. Steps are FIXED_TIMESTEP apart, so they are also the recording's
+// clock, and time spent paused doesn't appear in it.
+
+// A snapshot, and the first step that saw it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub step: u64,
+    pub info: SystemInfo,
+}
+
+// An operator action, and the step it was taken before
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedAction {
+    pub step: u64,
+    pub action: Action,
+}
+
+// First line of a recording: what is needed to reproduce the session exactly,
+// the reactor seed included
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub config: AppConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordLine {
+    Header(Box<RecordingHeader>),
+    Frame(Box<RecordedFrame>),
+    Action(RecordedAction),
+}
+
+// Appends a session to a recording as it runs
+pub struct Recorder {
+    path: PathBuf,
+    // None once a write has failed; the session keeps running unrecorded
+    writer: Option<BufWriter<File>>,
+    frames: usize,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>, header: RecordingHeader) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &RecordLine::Header(Box::new(header)))?;
+        writeln!(writer)?;
+
+        Ok(Recorder {
+            path,
+            writer: Some(writer),
+            frames: 0,
+        })
+    }
+
+    pub fn frame(&mut self, step: u64, info: &SystemInfo) {
+        let frame = RecordedFrame { step, info: info.clone() };
+        if self.write(&RecordLine::Frame(Box::new(frame))) {
+            self.frames += 1;
+        }
+    }
+
+    pub fn action(&mut self, step: u64, action: &Action) {
+        self.write(&RecordLine::Action(RecordedAction { step, action: action.clone() }));
+    }
+
+    // Whether the line made it to the file
+    fn write(&mut self, line: &RecordLine) -> bool {
+        let written = self.writer.as_mut().map(|writer| {
+            serde_json::to_writer(&mut *writer, line)?;
+            writeln!(writer)?;
+            // Flush every line so a crash still leaves a usable capture
+            writer.flush()?;
+            Ok::<_, anyhow::Error>(())
+        });
+        match written {
+            Some(Ok(())) => true,
+            Some(Err(_)) => {
+                self.writer = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        match self.writer {
+            Some(_) => format!("● REC {} ({} snapshots)", self.path.display(), self.frames),
+            None => format!("REC FAILED: {} (stopped after {} snapshots)", self.path.display(), self.frames),
+        }
+    }
+}
+
+// Feeds a recording back through the reactor step by step: every step gets the
+// snapshot and the operator actions it got when the session was recorded. The
+// position only moves through `Playback::step`, so the app keeps the recording
+// in lockstep with the simulation. Seeking moves the recording on but not the
+// reactor, which carries on from wherever it is.
+pub struct ReplaySource {
+    header: Option<RecordingHeader>,
+    frames: Vec<RecordedFrame>,
+    actions: Vec<RecordedAction>,
+    // Steps played; this keeps counting past the end, where the last snapshot holds
+    step: u64,
+    speed: f32,
+    paused: bool,
+    // Index of the last frame handed out, so each frame is published once
+    current: Option<usize>,
+}
+
+impl ReplaySource {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+
+        let mut header = None;
+        let mut frames = Vec::new();
+        let mut actions = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: RecordLine = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: invalid recording line", path.display(), number + 1))?;
+            match record {
+                RecordLine::Header(h) => header = Some(*h),
+                RecordLine::Frame(frame) => frames.push(*frame),
+                RecordLine::Action(action) => actions.push(action),
+            }
+        }
+
+        if frames.is_empty() {
+            bail!("Recording {} contains no snapshots", path.display());
+        }
+        let mut replay = Self::new(frames);
+        replay.header = header;
+        // Stable, so actions taken before the same step keep their order
+        actions.sort_by_key(|action| action.step);
+        replay.actions = actions;
+        Ok(replay)
+    }
+
+    pub fn new(mut frames: Vec<RecordedFrame>) -> Self {
+        frames.sort_by_key(|frame| frame.step);
+        ReplaySource {
+            header: None,
+            frames,
+            actions: Vec::new(),
+            step: 0,
+            speed: 1.0,
+            paused: false,
+            current: None,
+        }
+    }
+
+    // The settings the session was recorded with, if the file has them
+    pub fn config(&self) -> Option<&AppConfig> {
+        self.header.as_ref().map(|header| &header.config)
+    }
+
+    // The step of the last thing recorded
+    fn end(&self) -> u64 {
+        let frame = self.frames.last().map_or(0, |frame| frame.step);
+        let action = self.actions.last().map_or(0, |action| action.step);
+        frame.max(action)
+    }
+
+    // Index of the last frame at or before `step`
+    fn frame_at(&self, step: u64) -> Option<usize> {
+        self.frames
+            .partition_point(|frame| frame.step <= step)
+            .checked_sub(1)
+    }
+}
+
+fn step_time(step: u64) -> Duration {
+    Duration::from_secs_f64(step as f64 * FIXED_TIMESTEP as f64)
+}
+
+impl MetricsSource for ReplaySource {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        let index = self.frame_at(self.step)?;
+        if self.current == Some(index) {
+            return None;
+        }
+        self.current = Some(index);
+        Some(self.frames[index].info.clone())
+    }
+
+    fn playback(&self) -> Option<&dyn Playback> {
+        Some(self)
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        Some(self)
+    }
+}
+
+impl Playback for ReplaySource {
+    fn position(&self) -> Duration {
+        step_time(self.step.min(self.end()))
+    }
+
+    fn duration(&self) -> Duration {
+        step_time(self.end())
+    }
+
+    fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let start = self.actions.partition_point(|action| action.step < self.step);
+        self.actions[start..]
+            .iter()
+            .take_while(|action| action.step == self.step)
+            .map(|action| action.action.clone())
+            .collect()
+    }
+
+    fn step(&mut self) {
+        self.step += 1;
+    }
+
+    fn seek(&mut self, position: Duration) {
+        let step = (position.as_secs_f64() / FIXED_TIMESTEP as f64).round() as u64;
+        self.step = step.min(self.end());
+        // Republish whatever frame is under the new position
+        self.current = None;
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED);
+    }
+}
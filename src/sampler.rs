@@ -0,0 +1,125 @@
+use crate::cgroup::CgroupFs;
+use crate::metrics::MetricsSource;
+use crate::network::InterfaceFilter;
+use crate::procfs::{ProcFs, DEFAULT_PROC_ROOT};
+use crate::sensors::{TemperatureProbe, TemperatureSensor, DEFAULT_SYS_ROOT};
+use crate::system::{SystemInfo, SystemMonitor};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SamplerConfig {
+    // How often CPU, memory and temperature are sampled
+    pub interval: Duration,
+    // How often the (expensive) process table is rescanned
+    pub process_interval: Duration,
+    pub sensor: TemperatureSensor,
+    // Network interfaces that count towards throughput
+    pub interfaces: InterfaceFilter,
+    // Where procfs is mounted; another root reads a container's host, or fixtures
+    pub proc_root: PathBuf,
+    // Where sysfs is mounted, for the temperature sensors
+    pub sys_root: PathBuf,
+    // Monitor this cgroup rather than the whole machine
+    pub cgroup: Option<CgroupFs>,
+}
+
+impl Default for SamplerConfig {
+    fn default() -> Self {
+        SamplerConfig {
+            interval: Duration::from_millis(500),
+            process_interval: Duration::from_secs(5),
+            sensor: TemperatureSensor::default(),
+            interfaces: InterfaceFilter::default(),
+            proc_root: PathBuf::from(DEFAULT_PROC_ROOT),
+            sys_root: PathBuf::from(DEFAULT_SYS_ROOT),
+            cgroup: None,
+        }
+    }
+}
+
+// Polls a metrics source on a background thread and publishes snapshots over
+// a channel, so the render loop never waits on the source
+pub struct Sampler {
+    snapshots: Receiver<SystemInfo>,
+    // Dropping this sender wakes the thread up and tells it to exit
+    stop: Option<Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Sampler {
+    // Samples the host through sysinfo
+    pub fn system(config: SamplerConfig) -> Self {
+        // CPU usage is a delta between two refreshes, which sysinfo cannot
+        // compute meaningfully below its own minimum interval
+        let interval = config.interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        let procfs = ProcFs::new(config.proc_root);
+        let probe = TemperatureProbe::new(config.sensor, config.sys_root);
+        let monitor = SystemMonitor::new(probe, config.process_interval, config.interfaces, procfs, config.cgroup);
+        Self::spawn_primed(monitor, interval)
+    }
+
+    pub fn spawn<S: MetricsSource + 'static>(source: S, interval: Duration) -> Self {
+        Self::start(source, interval, false)
+    }
+
+    // For a source that reports deltas between samples, like CPU usage and
+    // the per-second rates: the first sample only sets the baseline, and the
+    // first snapshot is published an interval later
+    pub fn spawn_primed<S: MetricsSource + 'static>(source: S, interval: Duration) -> Self {
+        Self::start(source, interval, true)
+    }
+
+    fn start<S: MetricsSource + 'static>(mut source: S, interval: Duration, prime: bool) -> Self {
+        let (snapshot_tx, snapshot_rx) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name("metrics-sampler".to_string())
+            .spawn(move || {
+                if prime {
+                    source.sample();
+                    if !matches!(stop_rx.recv_timeout(interval), Err(RecvTimeoutError::Timeout)) {
+                        return;
+                    }
+                }
+                loop {
+                    if let Some(info) = source.sample()
+                        && snapshot_tx.send(info).is_err()
+                    {
+                        return;
+                    }
+                    match stop_rx.recv_timeout(interval) {
+                        Err(RecvTimeoutError::Timeout) => {}
+                        _ => return,
+                    }
+                }
+            })
+            .expect("failed to spawn metrics sampler thread");
+
+        Sampler {
+            snapshots: snapshot_rx,
+            stop: Some(stop_tx),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl MetricsSource for Sampler {
+    // The most recent snapshot published since the last call, if any
+    fn sample(&mut self) -> Option<SystemInfo> {
+        self.snapshots.try_iter().last()
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::system::SystemInfo;
+use anyhow::{bail, Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+// Scripted CPU load shapes, for demos and for seeing how the reactor copes
+// with a particular load. A scenario is a sequence of segments, read from a
+// JSON file, and is blended with the machine's real load while it plays.
+
+// Where scenario files are looked for unless told otherwise
+pub const DEFAULT_SCENARIO_DIR: &str = "scenarios";
+
+// How a scenario's level combines with the real CPU load
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Blend {
+    // The real load is ignored
+    #[default]
+    Override,
+    // The scenario comes on top of the real load
+    Add,
+    // Whichever is higher
+    Max,
+}
+
+impl Blend {
+    pub fn next(self) -> Self {
+        match self {
+            Blend::Override => Blend::Add,
+            Blend::Add => Blend::Max,
+            Blend::Max => Blend::Override,
+        }
+    }
+
+    fn combine(self, real: f32, level: f32) -> f32 {
+        let load = match self {
+            Blend::Override => level,
+            Blend::Add => real + level,
+            Blend::Max => real.max(level),
+        };
+        load.clamp(0.0, 100.0)
+    }
+}
+
+impl FromStr for Blend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "override" => Ok(Blend::Override),
+            "add" => Ok(Blend::Add),
+            "max" => Ok(Blend::Max),
+            other => Err(format!("unknown blend '{}' (expected override, add or max)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Blend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Blend::Override => write!(f, "override"),
+            Blend::Add => write!(f, "add"),
+            Blend::Max => write!(f, "max"),
+        }
+    }
+}
+
+fn half() -> f32 {
+    0.5
+}
+
+// One stretch of a scenario. Levels are CPU load in %, times in seconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Segment {
+    Hold { level: f32, duration: f32 },
+    Ramp { from: f32, to: f32, duration: f32 },
+    Sine { mean: f32, amplitude: f32, period: f32, duration: f32 },
+    // `duty` is the fraction of each period spent high
+    Square {
+        low: f32,
+        high: f32,
+        period: f32,
+        #[serde(default = "half")]
+        duty: f32,
+        duration: f32,
+    },
+    // Brownian motion: `volatility` is the typical drift over one second
+    RandomWalk { start: f32, volatility: f32, min: f32, max: f32, duration: f32 },
+    // A `width`-second burst to `peak` at the start of every `every` seconds
+    Spikes { base: f32, peak: f32, every: f32, width: f32, duration: f32 },
+    // Straight lines between (time, level) points; lasts until the last one
+    Piecewise { points: Vec<(f32, f32)> },
+}
+
+impl Segment {
+    pub fn duration(&self) -> f32 {
+        match self {
+            Segment::Hold { duration, .. }
+            | Segment::Ramp { duration, .. }
+            | Segment::Sine { duration, .. }
+            | Segment::Square { duration, .. }
+            | Segment::RandomWalk { duration, .. }
+            | Segment::Spikes { duration, .. } => *duration,
+            Segment::Piecewise { points } => points.last().map_or(0.0, |&(t, _)| t),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let periodic = match self {
+            Segment::Sine { period, .. } | Segment::Square { period, .. } => Some(*period),
+            Segment::Spikes { every, .. } => Some(*every),
+            _ => None,
+        };
+        if periodic.is_some_and(|period| period <= 0.0) {
+            bail!("a {} segment needs a period above zero", self.name());
+        }
+        if let Segment::RandomWalk { min, max, .. } = self
+            && min > max
+        {
+            bail!("a random_walk segment needs min <= max");
+        }
+        if let Segment::Piecewise { points } = self {
+            if points.is_empty() {
+                bail!("a piecewise segment needs at least one point");
+            }
+            if points.windows(2).any(|pair| pair[1].0 < pair[0].0) {
+                bail!("piecewise points must be in time order");
+            }
+        }
+        if self.duration() < 0.0 {
+            bail!("a {} segment can't have a negative duration", self.name());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Segment::Hold { .. } => "hold",
+            Segment::Ramp { .. } => "ramp",
+            Segment::Sine { .. } => "sine",
+            Segment::Square { .. } => "square",
+            Segment::RandomWalk { .. } => "random_walk",
+            Segment::Spikes { .. } => "spikes",
+            Segment::Piecewise { .. } => "piecewise",
+        }
+    }
+
+    // Level `t` seconds in. Random walks keep their position in `walk`.
+    fn level(&self, t: f32, walk: f32) -> f32 {
+        match self {
+            Segment::Hold { level, .. } => *level,
+            Segment::Ramp { from, to, duration } => {
+                from + (to - from) * (t / duration.max(f32::EPSILON)).clamp(0.0, 1.0)
+            }
+            Segment::Sine { mean, amplitude, period, .. } => mean + amplitude * (TAU * t / period).sin(),
+            Segment::Square { low, high, period, duty, .. } => {
+                if (t / period).fract() < *duty { *high } else { *low }
+            }
+            Segment::RandomWalk { .. } => walk,
+            Segment::Spikes { base, peak, every, width, .. } => {
+                if t % every < *width { *peak } else { *base }
+            }
+            Segment::Piecewise { points } => {
+                let next = points.iter().position(|&(time, _)| time > t).unwrap_or(points.len());
+                match (next.checked_sub(1).map(|i| points[i]), points.get(next)) {
+                    (Some((t0, l0)), Some(&(t1, l1))) => l0 + (l1 - l0) * (t - t0) / (t1 - t0),
+                    (Some((_, level)), None) | (None, Some(&(_, level))) => level,
+                    (None, None) => 0.0,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub blend: Blend,
+    // Start over after the last segment instead of stopping
+    #[serde(default)]
+    pub repeat: bool,
+    // Seeds the random walks, so a scenario plays the same every time
+    #[serde(default)]
+    pub seed: u64,
+    pub segments: Vec<Segment>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario {}", path.display()))?;
+        let scenario: Scenario = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid scenario {}", path.display()))?;
+        scenario.validate()
+            .with_context(|| format!("Invalid scenario {}", path.display()))?;
+        Ok(scenario)
+    }
+
+    // Every *.json scenario in a directory, in file name order, along with
+    // the errors for any that couldn't be loaded. A missing directory is empty.
+    pub fn load_dir(dir: impl AsRef<Path>) -> (Vec<Scenario>, Vec<String>) {
+        let Ok(entries) = fs::read_dir(dir.as_ref()) else {
+            return (Vec::new(), Vec::new());
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let mut scenarios = Vec::new();
+        let mut errors = Vec::new();
+        for path in paths {
+            match Scenario::load(&path) {
+                Ok(scenario) => scenarios.push(scenario),
+                Err(err) => errors.push(format!("{:#}", err)),
+            }
+        }
+        (scenarios, errors)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.segments.is_empty() {
+            bail!("'{}' has no segments", self.name);
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            segment.validate().with_context(|| format!("segment {}", i + 1))?;
+        }
+        if self.repeat && self.duration() <= 0.0 {
+            bail!("'{}' repeats but lasts no time", self.name);
+        }
+        Ok(())
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.segments.iter().map(Segment::duration).sum()
+    }
+
+    // The original load simulation: a triangle wave between 20% and 95%,
+    // sweeping at 15% a second
+    pub fn triangle() -> Self {
+        Scenario {
+            name: "Triangle".to_string(),
+            description: "Sweep between 20% and 95% load".to_string(),
+            blend: Blend::Override,
+            repeat: true,
+            seed: 0,
+            segments: vec![
+                Segment::Ramp { from: 20.0, to: 95.0, duration: 5.0 },
+                Segment::Ramp { from: 95.0, to: 20.0, duration: 5.0 },
+            ],
+        }
+    }
+
+    // Always on offer, whatever is on disk
+    pub fn builtin() -> Vec<Scenario> {
+        let scenario = |name: &str, description: &str, segments| Scenario {
+            name: name.to_string(),
+            description: description.to_string(),
+            blend: Blend::Override,
+            repeat: true,
+            seed: 0,
+            segments,
+        };
+        vec![
+            Scenario::triangle(),
+            scenario("Slow ramp", "Climb from idle to full load over two minutes", vec![
+                Segment::Ramp { from: 0.0, to: 100.0, duration: 120.0 },
+                Segment::Hold { level: 100.0, duration: 30.0 },
+            ]),
+            scenario("Sine", "Swell between 20% and 80% every 30 seconds", vec![
+                Segment::Sine { mean: 50.0, amplitude: 30.0, period: 30.0, duration: 30.0 },
+            ]),
+            scenario("Square", "Step between 10% and 90% every 10 seconds", vec![
+                Segment::Square { low: 10.0, high: 90.0, period: 20.0, duty: 0.5, duration: 20.0 },
+            ]),
+            scenario("Random walk", "Wander between 10% and 95%", vec![
+                Segment::RandomWalk { start: 40.0, volatility: 8.0, min: 10.0, max: 95.0, duration: 300.0 },
+            ]),
+            scenario("Spikes", "Mostly idle, with a 2 second burst every 15 seconds", vec![
+                Segment::Spikes { base: 15.0, peak: 100.0, every: 15.0, width: 2.0, duration: 15.0 },
+            ]),
+        ]
+    }
+}
+
+// A scenario being played
+pub struct ScenarioPlayer {
+    pub scenario: Scenario,
+    pub blend: Blend,
+    // Seconds since the start of the current pass
+    pub elapsed: f32,
+    // None once a scenario that doesn't repeat has ended
+    pub level: Option<f32>,
+    walk: f32,
+    walk_segment: Option<usize>,
+    rng: StdRng,
+}
+
+impl ScenarioPlayer {
+    pub fn new(scenario: Scenario) -> Self {
+        let mut player = ScenarioPlayer {
+            blend: scenario.blend,
+            elapsed: 0.0,
+            level: None,
+            walk: 0.0,
+            walk_segment: None,
+            rng: StdRng::seed_from_u64(scenario.seed),
+            scenario,
+        };
+        player.level = player.level_at(0.0, 0.0);
+        player
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.level.is_none()
+    }
+
+    // Move `dt` seconds on and return the new level, or None once finished
+    pub fn advance(&mut self, dt: f32) -> Option<f32> {
+        if self.is_finished() {
+            return None;
+        }
+        self.elapsed += dt;
+        let duration = self.scenario.duration();
+        if self.elapsed >= duration && self.scenario.repeat {
+            self.elapsed %= duration;
+            self.walk_segment = None;
+        }
+        self.level = self.level_at(self.elapsed, dt);
+        self.level
+    }
+
+    // Blend the current level into a snapshot of the real load
+    pub fn apply(&self, info: &mut SystemInfo) {
+        let Some(level) = self.level else {
+            return;
+        };
+        for core in &mut info.core_usage {
+            *core = self.blend.combine(*core, level);
+        }
+        info.cpu_usage = if info.core_usage.is_empty() {
+            self.blend.combine(info.cpu_usage, level)
+        } else {
+            info.core_usage.iter().sum::<f32>() / info.core_usage.len() as f32
+        };
+    }
+
+    fn level_at(&mut self, t: f32, dt: f32) -> Option<f32> {
+        let mut start = 0.0;
+        for (index, segment) in self.scenario.segments.iter().enumerate() {
+            let duration = segment.duration();
+            if t < start + duration {
+                if let Segment::RandomWalk { start: level, volatility, min, max, .. } = *segment {
+                    self.walk = if self.walk_segment == Some(index) {
+                        // A uniform step with unit variance, scaled to the time step
+                        let step = self.rng.random_range(-1.0..=1.0) * 3.0f32.sqrt();
+                        (self.walk + volatility * dt.sqrt() * step).clamp(min, max)
+                    } else {
+                        level.clamp(min, max)
+                    };
+                    self.walk_segment = Some(index);
+                }
+                return Some(segment.level(t - start, self.walk).clamp(0.0, 100.0));
+            }
+            start += duration;
+        }
+        None
+    }
+}
+
+// The scenarios offered in the menu: the built-in ones, then any in `dir`
+pub fn library(dir: impl AsRef<Path>) -> (Vec<Scenario>, Vec<String>) {
+    let (found, errors) = Scenario::load_dir(dir);
+    let mut scenarios = Scenario::builtin();
+    scenarios.extend(found);
+    (scenarios, errors)
+}
+
+// A scenario file, or one from `library` by name
+pub fn find(name_or_path: &str, library: &[Scenario]) -> Result<Scenario> {
+    let path = Path::new(name_or_path);
+    if path.is_file() {
+        return Scenario::load(path);
+    }
+    match library.iter().find(|s| s.name.eq_ignore_ascii_case(name_or_path.trim())) {
+        Some(scenario) => Ok(scenario.clone()),
+        None => {
+            let names: Vec<_> = library.iter().map(|s| s.name.as_str()).collect();
+            bail!("unknown scenario '{}' (expected a file or one of: {})", name_or_path, names.join(", "))
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use sysinfo::Components;
+
+pub const DEFAULT_SYS_ROOT: &str = "/sys";
+
+// Which hardware sensor should be reported as the CPU temperature
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureSensor {
+    // The whole-package sensor (Intel "Package id", AMD "Tctl"/"Tdie")
+    #[default]
+    Package,
+    // A single core sensor ("Core N")
+    Core(usize),
+    // Whichever sensor currently reads the highest temperature
+    Hottest,
+}
+
+impl FromStr for TemperatureSensor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "package" => Ok(TemperatureSensor::Package),
+            "hottest" | "max" => Ok(TemperatureSensor::Hottest),
+            _ => s
+                .strip_prefix("core")
+                .map(|n| n.trim_start_matches([':', '-', ' ']))
+                .and_then(|n| n.parse().ok())
+                .map(TemperatureSensor::Core)
+                .ok_or_else(|| format!("unknown sensor '{}' (expected package, hottest or core:N)", s)),
+        }
+    }
+}
+
+impl fmt::Display for TemperatureSensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemperatureSensor::Package => write!(f, "package"),
+            TemperatureSensor::Core(n) => write!(f, "core:{}", n),
+            TemperatureSensor::Hottest => write!(f, "hottest"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensorReading {
+    pub label: String,
+    pub celsius: f32,
+}
+
+// Reads CPU temperatures from sysinfo's `Components`, falling back to
+// `class/hwmon` and `class/thermal` under the sysfs root when sysinfo finds
+// nothing
+pub struct TemperatureProbe {
+    // Only for the real /sys: sysinfo can't be pointed anywhere else
+    components: Option<Components>,
+    sensor: TemperatureSensor,
+    sysfs_root: PathBuf,
+}
+
+impl TemperatureProbe {
+    pub fn new(sensor: TemperatureSensor, sysfs_root: impl Into<PathBuf>) -> Self {
+        let sysfs_root = sysfs_root.into();
+        TemperatureProbe {
+            components: (sysfs_root == Path::new(DEFAULT_SYS_ROOT)).then(Components::new_with_refreshed_list),
+            sensor,
+            sysfs_root,
+        }
+    }
+
+    pub fn sensor(&self) -> TemperatureSensor {
+        self.sensor
+    }
+
+    pub fn read(&mut self) -> Option<SensorReading> {
+        let mut readings: Vec<SensorReading> = Vec::new();
+        if let Some(components) = &mut self.components {
+            components.refresh(false);
+            readings = components
+                .iter()
+                .filter_map(|c| {
+                    c.temperature().map(|t| SensorReading {
+                        label: c.label().to_string(),
+                        celsius: t,
+                    })
+                })
+                .collect();
+        }
+
+        if readings.is_empty() {
+            readings = read_hwmon(&self.sysfs_root.join("class/hwmon"));
+        }
+        if readings.is_empty() {
+            readings = read_thermal_zones(&self.sysfs_root.join("class/thermal"));
+        }
+
+        select_reading(readings, self.sensor)
+    }
+}
+
+fn select_reading(readings: Vec<SensorReading>, sensor: TemperatureSensor) -> Option<SensorReading> {
+    // Sensors that report 0°C or absurd values are disconnected inputs
+    let mut readings: Vec<SensorReading> = readings
+        .into_iter()
+        .filter(|r| r.celsius.is_finite() && r.celsius > 0.0 && r.celsius < 150.0)
+        .collect();
+
+    let hottest = |readings: &mut Vec<SensorReading>| {
+        readings.sort_by(|a, b| b.celsius.total_cmp(&a.celsius));
+        readings.first().cloned()
+    };
+
+    match sensor {
+        TemperatureSensor::Package => {
+            let package = readings.iter().find(|r| is_package_label(&r.label)).cloned();
+            // Without a package sensor, the hottest CPU sensor stands in; a
+            // disk or GPU running hot says nothing about the CPU
+            package.or_else(|| {
+                readings.retain(|r| is_cpu_label(&r.label));
+                hottest(&mut readings)
+            })
+        }
+        TemperatureSensor::Core(n) => readings
+            .into_iter()
+            .find(|r| core_index(&r.label) == Some(n)),
+        TemperatureSensor::Hottest => hottest(&mut readings),
+    }
+}
+
+fn is_package_label(label: &str) -> bool {
+    let label = label.to_ascii_lowercase();
+    ["package", "tctl", "tdie", "x86_pkg_temp", "cpu_thermal"]
+        .iter()
+        .any(|key| label.contains(key))
+}
+
+fn is_cpu_label(label: &str) -> bool {
+    let lower = label.to_ascii_lowercase();
+    is_package_label(label)
+        || core_index(label).is_some()
+        || ["cpu", "coretemp", "k10temp", "zenpower"].iter().any(|key| lower.contains(key))
+}
+
+fn core_index(label: &str) -> Option<usize> {
+    let label = label.to_ascii_lowercase();
+    let start = label.find("core")? + "core".len();
+    label[start..].trim_start().split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    let raw = fs::read_to_string(path).ok()?;
+    raw.trim().parse::<f32>().ok().map(|m| m / 1000.0)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_hwmon(dir: &Path) -> Vec<SensorReading> {
+    let mut readings = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return readings;
+    };
+
+    for entry in entries.flatten() {
+        let hwmon = entry.path();
+        let chip = read_trimmed(&hwmon.join("name")).unwrap_or_default();
+        let Ok(files) = fs::read_dir(&hwmon) else {
+            continue;
+        };
+
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().into_owned();
+            let Some(prefix) = name.strip_suffix("_input").filter(|p| p.starts_with("temp")) else {
+                continue;
+            };
+            let Some(celsius) = read_millidegrees(&file.path()) else {
+                continue;
+            };
+            let label = read_trimmed(&hwmon.join(format!("{}_label", prefix)))
+                .unwrap_or_else(|| format!("{} {}", chip, prefix));
+            readings.push(SensorReading { label, celsius });
+        }
+    }
+
+    readings
+}
+
+fn read_thermal_zones(dir: &Path) -> Vec<SensorReading> {
+    let mut readings = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return readings;
+    };
+
+    for entry in entries.flatten() {
+        let zone = entry.path();
+        if !entry.file_name().to_string_lossy().starts_with("thermal_zone") {
+            continue;
+        }
+        if let Some(celsius) = read_millidegrees(&zone.join("temp")) {
+            let label = read_trimmed(&zone.join("type"))
+                .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+            readings.push(SensorReading { label, celsius });
+        }
+    }
+
+    readings
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A real load generator, so the reactor can be pushed with the machine's own
+// load rather than a simulated one. Workers alternate between work and sleep
+// to hit a target utilization; the work is fed through `black_box` so the
+// optimizer can't throw it away.
+
+// Length of one work/sleep cycle. Short enough that a sampler sees a steady
+// utilization, long enough that the sleep is honoured.
+const DUTY_PERIOD: Duration = Duration::from_millis(100);
+
+// How often a busy worker checks the clock and the stop flag
+const CHUNK_ITERATIONS: u64 = 20_000;
+
+const PAGE_SIZE: usize = 4096;
+
+// Bounds on the size of each allocation made by an allocation stressor
+const MIN_ALLOCATION: usize = 64 * 1024;
+const MAX_ALLOCATION: usize = 4 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StressConfig {
+    // CPU workers; 0 starts one per core
+    pub workers: usize,
+    // Target busy time of every worker, 0 - 100%
+    pub utilization: f32,
+    // Workers streaming through a `memory_mb` buffer, loading memory bandwidth
+    pub memory_workers: usize,
+    // Workers allocating, touching and freeing up to `memory_mb` at a time
+    pub alloc_workers: usize,
+    pub memory_mb: usize,
+    // Stop by itself after this long
+    pub duration: Option<Duration>,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        StressConfig {
+            workers: 0,
+            utilization: 100.0,
+            memory_workers: 0,
+            alloc_workers: 0,
+            memory_mb: 256,
+            duration: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stressor {
+    Cpu,
+    MemoryBandwidth,
+    Allocation,
+}
+
+pub struct StressTest {
+    pub config: StressConfig,
+    // CPU workers actually started, after resolving "one per core"
+    pub cpu_workers: usize,
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    handles: Vec<thread::JoinHandle<()>>,
+}
+
+impl StressTest {
+    pub fn start(config: StressConfig) -> Self {
+        let cpu_workers = match config.workers {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let stressors = std::iter::repeat_n(Stressor::Cpu, cpu_workers)
+            .chain(std::iter::repeat_n(Stressor::MemoryBandwidth, config.memory_workers))
+            .chain(std::iter::repeat_n(Stressor::Allocation, config.alloc_workers));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let started = Instant::now();
+        let handles = stressors
+            .enumerate()
+            .filter_map(|(index, stressor)| {
+                let worker = Worker {
+                    stressor,
+                    seed: index as u64 + 1,
+                    busy: DUTY_PERIOD.mul_f32(config.utilization.clamp(0.0, 100.0) / 100.0),
+                    memory_bytes: config.memory_mb.max(1) * 1024 * 1024,
+                    deadline: config.duration.map(|duration| started + duration),
+                    stop: stop.clone(),
+                };
+                // A worker that can't be spawned is just missing from the load
+                thread::Builder::new()
+                    .name(format!("stress-{}", index))
+                    .spawn(move || worker.run())
+                    .ok()
+            })
+            .collect();
+
+        StressTest {
+            config,
+            cpu_workers,
+            started,
+            stop,
+            handles,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // The duration limit has passed; the workers have stopped or are stopping
+    pub fn is_expired(&self) -> bool {
+        self.config.duration.is_some_and(|duration| self.elapsed() >= duration)
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.handles.len()
+    }
+
+    // Signal every worker and wait for them to finish their current chunk
+    pub fn stop(mut self) {
+        self.join();
+    }
+
+    fn join(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for StressTest {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+struct Worker {
+    stressor: Stressor,
+    seed: u64,
+    // Working part of each duty period
+    busy: Duration,
+    memory_bytes: usize,
+    deadline: Option<Instant>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Worker {
+    fn run(self) {
+        let mut state = Xorshift(self.seed);
+        let mut buffer = Vec::new();
+        let mut allocations = VecDeque::new();
+        let mut allocated = 0;
+        if self.stressor == Stressor::MemoryBandwidth {
+            buffer = vec![0u64; self.memory_bytes / 8];
+        }
+
+        loop {
+            let period_start = Instant::now();
+            if self.stop.load(Ordering::SeqCst) || self.deadline.is_some_and(|d| period_start >= d) {
+                return;
+            }
+
+            while period_start.elapsed() < self.busy {
+                match self.stressor {
+                    Stressor::Cpu => cpu_chunk(&mut state),
+                    Stressor::MemoryBandwidth => bandwidth_chunk(&mut buffer, &mut state),
+                    Stressor::Allocation => {
+                        allocation_chunk(&mut allocations, &mut allocated, self.memory_bytes, &mut state)
+                    }
+                }
+                if self.stop.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+
+            if let Some(idle) = DUTY_PERIOD.checked_sub(period_start.elapsed()) {
+                thread::sleep(idle);
+            }
+        }
+    }
+}
+
+// Cheap, deterministic pseudo-random numbers that the optimizer can't predict
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+// Integer and floating point arithmetic in a dependency chain
+fn cpu_chunk(state: &mut Xorshift) {
+    let mut x = black_box(state.next() as f64);
+    for _ in 0..CHUNK_ITERATIONS {
+        x = (x * 1.000_001 + 0.5).sqrt() + (state.next() & 0xff) as f64;
+    }
+    black_box(x);
+}
+
+// Read and write a stretch of the buffer, far bigger than any cache
+fn bandwidth_chunk(buffer: &mut [u64], state: &mut Xorshift) {
+    if buffer.is_empty() {
+        return;
+    }
+    let stretch = (CHUNK_ITERATIONS as usize * 8).min(buffer.len());
+    let start = state.next() as usize % (buffer.len() - stretch + 1);
+    let mut sum = 0u64;
+    for word in &mut buffer[start..start + stretch] {
+        sum = sum.wrapping_add(*word);
+        *word = sum;
+    }
+    black_box(sum);
+}
+
+// Allocate a block, touch every page so it is really backed, and free the
+// oldest blocks once more than `limit` bytes are held
+fn allocation_chunk(allocations: &mut VecDeque<Vec<u8>>, allocated: &mut usize, limit: usize, state: &mut Xorshift) {
+    let size = MIN_ALLOCATION + state.next() as usize % (MAX_ALLOCATION - MIN_ALLOCATION);
+    let mut block = vec![0u8; size];
+    for page in block.iter_mut().step_by(PAGE_SIZE) {
+        *page = state.next() as u8;
+    }
+    *allocated += block.len();
+    allocations.push_back(black_box(block));
+
+    while *allocated > limit
+        && let Some(oldest) = allocations.pop_front()
+    {
+        *allocated -= oldest.len();
+    }
+}
@@ -1,58 +1,245 @@
-use sysinfo::{System, RefreshKind};
+use sysinfo::{CpuRefreshKind, DiskRefreshKind, Disks, MemoryRefreshKind, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
+use crate::metrics::MetricsSource;
+use serde::{Deserialize, Serialize};
+use crate::sensors::TemperatureProbe;
+use crate::cgroup::{CgroupFs, CgroupReading, CgroupStats};
+use crate::disk::{total_rates, DiskStats, FilesystemUsage};
+use crate::network::{InterfaceFilter, InterfaceStats};
+use crate::psi::SystemPressure;
+use crate::procfs::{DiskCounters, InterfaceCounters, KernelCounters, KernelRates, ProcFs};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
+// A snapshot of the metrics the reactor is driven by
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SystemInfo {
-    sys: System,
     pub cpu_usage: f32,
+    // Usage of every logical core, in sysinfo's CPU order
+    pub core_usage: Vec<f32>,
     pub memory_usage: f32,
-    pub cpu_temp: f32,
+    // Bytes per second read from and written to all disks
+    pub disk_read_rate: f32,
+    pub disk_write_rate: f32,
+    // Block devices, by name; empty where /proc isn't available
+    pub disks: Vec<DiskStats>,
+    // Mounted filesystems, by mount point
+    pub filesystems: Vec<FilesystemUsage>,
+    // Pressure stall information; empty where the kernel lacks PSI
+    pub pressure: SystemPressure,
+    // The monitored cgroup, in cgroup mode; CPU, memory and disk figures
+    // are then the cgroup's, against its own limits
+    pub cgroup: Option<CgroupStats>,
+    // Swap in use, 0-100%; zero without any swap
+    pub swap_usage: f32,
+    // Runnable and waiting tasks, averaged over 1, 5 and 15 minutes
+    pub load_average: [f32; 3],
+    // Kernel events per second; zero where /proc isn't available
+    pub context_switch_rate: f32,
+    pub interrupt_rate: f32,
+    pub page_fault_rate: f32,
+    pub major_fault_rate: f32,
+    // Pages swapped in and out per second
+    pub swap_in_rate: f32,
+    pub swap_out_rate: f32,
+    // Watched network interfaces, by name; empty where /proc isn't available
+    pub network: Vec<InterfaceStats>,
+    // None when no temperature sensor could be found
+    pub cpu_temp: Option<f32>,
+    pub cpu_temp_label: Option<String>,
     pub uptime: u64,
     pub running_processes: usize,
 }
 
 impl SystemInfo {
-    pub fn new() -> Self {
-        let mut sys = System::new_with_specifics(
-            RefreshKind::everything()
+    // Bytes per second received and sent over all watched interfaces
+    pub fn network_throughput(&self) -> f32 {
+        self.network.iter().map(InterfaceStats::throughput).sum()
+    }
+
+    // Utilization of the busiest disk, 0.0 - 1.0
+    pub fn disk_saturation(&self) -> f32 {
+        self.disks.iter().map(|disk| disk.utilization).fold(0.0, f32::max)
+    }
+
+    // Usage of the fullest filesystem, 0.0 - 1.0
+    pub fn filesystem_usage(&self) -> f32 {
+        self.filesystems.iter().map(FilesystemUsage::usage).fold(0.0, f32::max)
+    }
+}
+
+// Collects `SystemInfo` snapshots from sysinfo, refreshing only what it reports
+pub struct SystemMonitor {
+    sys: System,
+    disks: Disks,
+    procfs: ProcFs,
+    last_counters: Option<KernelCounters>,
+    interfaces: InterfaceFilter,
+    last_interfaces: HashMap<String, InterfaceCounters>,
+    last_disks: HashMap<String, DiskCounters>,
+    cgroup: Option<CgroupFs>,
+    last_cgroup: Option<CgroupReading>,
+    // When the per-second rates (disk I/O, kernel and network counters) were last taken
+    last_rate_refresh: Instant,
+    probe: TemperatureProbe,
+    info: SystemInfo,
+    process_interval: Duration,
+    last_process_refresh: Option<Instant>,
+}
+
+impl SystemMonitor {
+    pub fn new(
+        probe: TemperatureProbe,
+        process_interval: Duration,
+        interfaces: InterfaceFilter,
+        procfs: ProcFs,
+        cgroup: Option<CgroupFs>,
+    ) -> Self {
+        let sys = System::new_with_specifics(
+            RefreshKind::nothing()
+                .with_cpu(CpuRefreshKind::nothing().with_cpu_usage())
+                .with_memory(MemoryRefreshKind::nothing().with_ram().with_swap())
         );
-        sys.refresh_all();
-        
-        SystemInfo {
+
+        SystemMonitor {
             sys,
-            cpu_usage: 0.0,
-            memory_usage: 0.0,
-            cpu_temp: 0.0,
-            uptime: 0,
-            running_processes: 0,
+            disks: Disks::new_with_refreshed_list_specifics(disk_refresh()),
+            procfs,
+            last_counters: None,
+            interfaces,
+            last_interfaces: HashMap::new(),
+            last_disks: HashMap::new(),
+            cgroup,
+            last_cgroup: None,
+            last_rate_refresh: Instant::now(),
+            probe,
+            info: SystemInfo::default(),
+            process_interval,
+            last_process_refresh: None,
         }
     }
+}
+
+// The free space on each filesystem; I/O comes from /proc/diskstats
+fn disk_refresh() -> DiskRefreshKind {
+    DiskRefreshKind::nothing().with_storage()
+}
+
+impl MetricsSource for SystemMonitor {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        self.sys.refresh_cpu_usage();
+        self.sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram().with_swap());
+
+        // Keep every core, and the average across them
+        self.info.core_usage.clear();
+        self.info.core_usage.extend(self.sys.cpus().iter().map(|cpu| cpu.cpu_usage()));
+
+        let cpu_count = self.info.core_usage.len().max(1);
+        self.info.cpu_usage = self.info.core_usage.iter().sum::<f32>() / cpu_count as f32;
 
-    pub fn update(&mut self) {
-        self.sys.refresh_all();
-        
-        // Calculate average CPU usage
-        let mut cpu_usage_total = 0.0;
-        let cpu_count = self.sys.cpus().len();
-        
-        for cpu in self.sys.cpus() {
-            cpu_usage_total += cpu.cpu_usage();
-        }
-        
-        self.cpu_usage = cpu_usage_total / cpu_count as f32;
-        
         // Memory usage percentage
         let total_memory = self.sys.total_memory();
         let used_memory = self.sys.used_memory();
-        
-        self.memory_usage = (used_memory as f32 / total_memory as f32) * 100.0;
-        
+
+        self.info.memory_usage = (used_memory as f32 / total_memory.max(1) as f32) * 100.0;
+        self.info.swap_usage = (self.sys.used_swap() as f32 / self.sys.total_swap().max(1) as f32) * 100.0;
+
+        let load = System::load_average();
+        self.info.load_average = [load.one as f32, load.five as f32, load.fifteen as f32];
+
+        let elapsed = self.last_rate_refresh.elapsed().as_secs_f32().max(f32::EPSILON);
+        self.last_rate_refresh = Instant::now();
+
+        // Free space on every mounted filesystem, each mount point once
+        self.disks.refresh_specifics(false, disk_refresh());
+        self.info.filesystems.clear();
+        for disk in self.disks.list() {
+            let mount_point = disk.mount_point().to_string_lossy();
+            if disk.total_space() == 0 || self.info.filesystems.iter().any(|fs| fs.mount_point == mount_point) {
+                continue;
+            }
+            self.info.filesystems.push(FilesystemUsage {
+                mount_point: mount_point.into_owned(),
+                total_bytes: disk.total_space(),
+                available_bytes: disk.available_space(),
+            });
+        }
+
+        // Kernel counters only become rates from the second reading on
+        let counters = self.procfs.counters();
+        let rates = match (&counters, &self.last_counters) {
+            (Some(now), Some(earlier)) => now.rates_since(earlier, elapsed),
+            _ => KernelRates::default(),
+        };
+        self.info.context_switch_rate = rates.context_switches;
+        self.info.interrupt_rate = rates.interrupts;
+        self.info.page_fault_rate = rates.page_faults;
+        self.info.major_fault_rate = rates.major_faults;
+        self.info.swap_in_rate = rates.swap_ins;
+        self.info.swap_out_rate = rates.swap_outs;
+        self.last_counters = counters;
+
+        // Likewise network rates; interfaces that come and go simply appear
+        // and disappear
+        let interfaces: Vec<_> = self.procfs.net_dev()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(name, _)| self.interfaces.matches(name))
+            .collect();
+        self.info.network = interfaces
+            .iter()
+            .map(|(name, now)| InterfaceStats::new(name, now, self.last_interfaces.get(name), elapsed))
+            .collect();
+        self.last_interfaces = interfaces.into_iter().collect();
+
+        // And per-device I/O, which also says how busy each device is. The
+        // totals come from the whole devices, not the filesystems on them,
+        // which would count a disk once for every mount.
+        let disks = self.procfs.diskstats().unwrap_or_default();
+        self.info.disks = disks
+            .iter()
+            .map(|(name, now)| DiskStats::new(name, now, self.last_disks.get(name), elapsed))
+            .collect();
+        self.last_disks = disks.into_iter().collect();
+        (self.info.disk_read_rate, self.info.disk_write_rate) = total_rates(&self.info.disks);
+
+        // The kernel averages stalls itself, so they're taken as they are
+        self.info.pressure = self.procfs.pressure();
+
+        // In cgroup mode the cgroup's own usage stands in for the machine's.
+        // It has no per-core figures, so every core it may use reports the
+        // same; a cgroup that has gone leaves the machine's figures.
+        let reading = self.cgroup.as_ref().and_then(CgroupFs::read);
+        self.info.cgroup = self.cgroup.as_ref().zip(reading.as_ref()).map(|(cgroup, now)| {
+            let memory = (used_memory, total_memory);
+            CgroupStats::new(cgroup.path(), now, self.last_cgroup.as_ref(), elapsed, cpu_count, memory)
+        });
+        if let Some(stats) = &self.info.cgroup {
+            self.info.cpu_usage = stats.cpu_usage;
+            self.info.core_usage = vec![stats.cpu_usage; (stats.cpu_limit.ceil() as usize).max(1)];
+            self.info.memory_usage = stats.memory_usage;
+            self.info.disk_read_rate = stats.io_read_rate;
+            self.info.disk_write_rate = stats.io_write_rate;
+        }
+        self.last_cgroup = reading;
+
         // Get system uptime
-        self.uptime = System::uptime();
-        
-        // Count running processes
-        self.running_processes = self.sys.processes().len();
-        
-        // Simulate temperature (sysinfo doesn't provide temp on all platforms)
-        // In a real app, you might use another crate or platform-specific code
-        self.cpu_temp = 40.0 + (self.cpu_usage * 0.5);
+        self.info.uptime = System::uptime();
+
+        // Scanning the process table is expensive, so it runs on a slower cadence
+        let processes_due = self.last_process_refresh
+            .is_none_or(|last| last.elapsed() >= self.process_interval);
+        if processes_due {
+            self.sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+            self.info.running_processes = self.sys.processes().len();
+            self.last_process_refresh = Some(Instant::now());
+        }
+
+        // Read the selected hardware sensor; report nothing rather than a guess
+        let reading = self.probe.read();
+        self.info.cpu_temp = reading.as_ref().map(|r| r.celsius);
+        self.info.cpu_temp_label = reading.map(|r| r.label);
+
+        Some(self.info.clone())
     }
-} 
\ No newline at end of file
+}
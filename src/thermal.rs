@@ -0,0 +1,187 @@
+use crate::reactor::approach;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Disk throughput (bytes/s) at which disk-driven pumps are completely bogged down
+const DISK_SATURATION_RATE: f32 = 200.0e6;
+// Memory usage below this leaves the pumps at full flow
+const MEMORY_FULL_FLOW_USAGE: f32 = 50.0;
+
+// Rate (1/s) at which the pumps spool towards their target flow
+const PUMP_SPEED: f32 = 0.8;
+
+// Heat transfer coefficients (1/s at full flow): core to primary coolant,
+// primary to secondary in the steam generator, secondary to the turbine
+const CORE_TRANSFER: f32 = 0.5;
+const STEAM_GENERATOR_TRANSFER: f32 = 0.5;
+const TURBINE_TRANSFER: f32 = 0.3;
+// The secondary side has its own feed pumps, so it never loses all its flow
+const SECONDARY_MIN_FLOW: f32 = 0.2;
+const FEEDWATER_TEMPERATURE: f32 = 100.0;
+
+// Extra core heating (°C/s) per unit of power (fission plus decay heat) when
+// the primary flow is lost. A floor keeps even a fresh, idle core warming up
+// unpumped.
+const STARVATION_HEATING: f32 = 1500.0;
+const STARVATION_HEAT_FLOOR: f32 = 0.1;
+
+// Coolant inventory boils off above this temperature, and is lost
+// faster when the pumps can't keep it moving
+const BOILING_POINT: f32 = 150.0;
+const COOLANT_SPEED: f32 = 0.5;
+
+// Extra primary pressure (kPa) with the busiest disk saturated: the loop
+// backs up behind it
+const DISK_BACKPRESSURE: f32 = 80.0;
+
+// Primary pressure (kPa) per % of time stalled, when the kernel measures
+// contention; a quarter of the time stalled reads like coolant at 500°C
+const STALL_PRESSURE: f32 = 4.0;
+
+// Filesystem usage at which the makeup water reservoir starts to drain, and
+// how far an empty reservoir lets the coolant inventory fall
+const RESERVOIR_DRAIN_USAGE: f32 = 0.75;
+const RESERVOIR_INVENTORY: f32 = 50.0;
+const RESERVOIR_SPEED: f32 = 0.2;
+
+// Turbine heat flow at nominal power, for the steam output gauge
+const NOMINAL_STEAM: f32 = 110.0;
+
+// What drives the primary coolant pumps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PumpDrive {
+    // Free memory: memory pressure starves the pumps
+    #[default]
+    Memory,
+    // Disk throughput: a saturated disk starves the pumps
+    DiskIo,
+}
+
+impl FromStr for PumpDrive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" | "mem" => Ok(PumpDrive::Memory),
+            "disk" | "io" | "disk-io" => Ok(PumpDrive::DiskIo),
+            other => Err(format!("unknown pump drive '{}' (expected memory or disk)", other)),
+        }
+    }
+}
+
+impl fmt::Display for PumpDrive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PumpDrive::Memory => write!(f, "memory"),
+            PumpDrive::DiskIo => write!(f, "disk"),
+        }
+    }
+}
+
+// Primary coolant loop, steam generator and secondary loop. Heat flows from
+// the core into the primary coolant, across the steam generator into the
+// secondary side, and out through the turbine.
+#[derive(Clone, Debug)]
+pub struct CoolantLoop {
+    pub drive: PumpDrive,
+    pub pump_flow: f32,              // 0.0 - 1.0 of rated flow
+    pub primary_temperature: f32,    // °C
+    pub secondary_temperature: f32,  // °C
+    pub heat_removed: f32,           // core to primary, °C/s
+    pub steam_output: f32,           // 0.0 - 1.0 of nominal
+    pub coolant_level: f32,          // 0 - 100% inventory
+    pub pressure: f32,               // kPa
+    pub reservoir: f32,              // 0 - 100% makeup water
+    pub disk_saturation: f32,        // 0.0 - 1.0 of the busiest disk
+    pub stall: Option<f32>,          // % of time stalled, from PSI
+}
+
+impl CoolantLoop {
+    pub fn new(drive: PumpDrive) -> Self {
+        CoolantLoop {
+            drive,
+            pump_flow: 1.0,
+            primary_temperature: 180.0,
+            secondary_temperature: 150.0,
+            heat_removed: 0.0,
+            steam_output: 0.0,
+            coolant_level: 95.0,
+            pressure: 101.3,
+            reservoir: 100.0,
+            disk_saturation: 0.0,
+            stall: None,
+        }
+    }
+
+    // Flow the pumps are being asked for, given memory usage (%) and disk
+    // throughput (bytes/s)
+    pub fn target_flow(&self, memory_usage: f32, disk_io_rate: f32) -> f32 {
+        match self.drive {
+            PumpDrive::Memory => {
+                let headroom = 100.0 - memory_usage.clamp(0.0, 100.0);
+                (headroom / (100.0 - MEMORY_FULL_FLOW_USAGE)).clamp(0.0, 1.0)
+            }
+            PumpDrive::DiskIo => 1.0 - (disk_io_rate / DISK_SATURATION_RATE).clamp(0.0, 1.0),
+        }
+    }
+
+    // Storage feeds the plant: a saturated disk backs up the loop, and a
+    // filling filesystem (usage 0.0 - 1.0) drains the makeup water reservoir
+    // that keeps the coolant topped up
+    pub fn update_storage(&mut self, disk_saturation: f32, filesystem_usage: f32, dt: f32) {
+        self.disk_saturation = disk_saturation.clamp(0.0, 1.0);
+        let headroom = (1.0 - filesystem_usage) / (1.0 - RESERVOIR_DRAIN_USAGE);
+        self.reservoir = approach(self.reservoir, headroom.clamp(0.0, 1.0) * 100.0, RESERVOIR_SPEED, dt);
+    }
+
+    // Contention measured by the kernel (PSI, % of time stalled) sets the
+    // pressure where it's available, in place of the coolant's temperature
+    pub fn update_contention(&mut self, stall: Option<f32>) {
+        self.stall = stall;
+    }
+
+    // Advance the loop by `dt` seconds and return the new core temperature
+    pub fn step(&mut self, core_temperature: f32, power: f32, target_flow: f32, dt: f32) -> f32 {
+        self.pump_flow = approach(self.pump_flow, target_flow, PUMP_SPEED, dt);
+        let secondary_flow = SECONDARY_MIN_FLOW + (1.0 - SECONDARY_MIN_FLOW) * self.pump_flow;
+
+        let core_to_primary = CORE_TRANSFER * self.pump_flow * (core_temperature - self.primary_temperature);
+        let primary_to_secondary =
+            STEAM_GENERATOR_TRANSFER * secondary_flow * (self.primary_temperature - self.secondary_temperature);
+        let to_turbine = TURBINE_TRANSFER * (self.secondary_temperature - FEEDWATER_TEMPERATURE).max(0.0);
+
+        self.primary_temperature += (core_to_primary - primary_to_secondary) * dt;
+        self.secondary_temperature += (primary_to_secondary - to_turbine) * dt;
+        self.heat_removed = core_to_primary;
+        self.steam_output = (to_turbine / NOMINAL_STEAM).max(0.0);
+
+        // Coolant left standing in the core takes on the core's temperature,
+        // so a starved loop pressurises and boils off even as the rest cools
+        let in_core = self.pump_flow * self.primary_temperature + (1.0 - self.pump_flow) * core_temperature;
+        let contention = match self.stall {
+            Some(stall) => stall * STALL_PRESSURE,
+            None => (in_core - FEEDWATER_TEMPERATURE) * 0.25,
+        };
+        let backpressure = DISK_BACKPRESSURE * self.disk_saturation;
+        self.pressure = (101.3 + contention + backpressure).clamp(100.0, 300.0);
+
+        // Whatever boils off can only be made up from the reservoir
+        let boil_off = (in_core - BOILING_POINT).max(0.0) * 0.08;
+        let makeup = (100.0 - self.reservoir) / 100.0 * RESERVOIR_INVENTORY;
+        let target_level = 100.0 - boil_off - makeup - (1.0 - self.pump_flow) * 40.0;
+        self.coolant_level = approach(self.coolant_level, target_level.clamp(0.0, 100.0), COOLANT_SPEED, dt);
+
+        // The core's own model assumes rated flow; whatever the pumps can't
+        // carry away stays in the core
+        let starvation = STARVATION_HEATING * (STARVATION_HEAT_FLOOR + power.max(0.0)) * (1.0 - self.pump_flow);
+        core_temperature + starvation * dt
+    }
+}
+
+impl Default for CoolantLoop {
+    fn default() -> Self {
+        Self::new(PumpDrive::default())
+    }
+}
@@ -0,0 +1,52 @@
+use crate::reactor::approach;
+
+// The turbine hall. Network traffic is the demand on the grid: the more the
+// machine sends and receives, the faster the turbine turns and the more the
+// generator puts out. Throughput is taken on a log scale, so an idle link
+// still turns it over and a saturated gigabit one runs it at full speed.
+
+// Throughput (bytes/s) below which the turbine stands still, and at which it
+// reaches rated speed
+const NETWORK_IDLE: f32 = 1.0e3;
+const NETWORK_FULL_SCALE: f32 = 125.0e6;
+
+// How quickly the rotor follows the demand; it has a lot of inertia
+const SPIN_RATE: f32 = 0.4;
+
+pub const RATED_RPM: f32 = 3000.0;
+pub const GENERATOR_RATING_MW: f32 = 1200.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct Turbine {
+    pub speed: f32,      // 0.0 - 1.0 of rated speed
+    pub output_mw: f32,
+    pub angle: f32,      // rotor position in turns, for animating it
+    // Disconnected from the reactor (SCRAM or meltdown), so spinning down
+    pub tripped: bool,
+}
+
+impl Turbine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Advance by `dt` seconds at `network_rate` bytes/s. A tripped turbine
+    // coasts down and generates nothing.
+    pub fn update(&mut self, network_rate: f32, tripped: bool, dt: f32) {
+        self.tripped = tripped;
+        let demand = if tripped { 0.0 } else { Self::demand(network_rate) };
+        self.speed = approach(self.speed, demand, SPIN_RATE, dt);
+        self.angle = (self.angle + self.speed * RATED_RPM / 60.0 * dt).fract();
+        self.output_mw = if tripped { 0.0 } else { GENERATOR_RATING_MW * self.speed };
+    }
+
+    // Speed the turbine is driven towards by a throughput, 0.0 - 1.0
+    pub fn demand(network_rate: f32) -> f32 {
+        let scale = (NETWORK_FULL_SCALE / NETWORK_IDLE).ln();
+        ((network_rate.max(NETWORK_IDLE) / NETWORK_IDLE).ln() / scale).clamp(0.0, 1.0)
+    }
+
+    pub fn rpm(&self) -> f32 {
+        self.speed * RATED_RPM
+    }
+}
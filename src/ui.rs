@@ -4,13 +4,20 @@ use ratatui::{
     symbols,
     text::{Span, Line},
     widgets::{
-        Block, Borders, Chart, Dataset, Gauge, Paragraph,
+        Block, Borders, Chart, Clear, Dataset, Gauge, Paragraph,
         canvas::{self, Canvas},
     },
     Frame,
 };
 use crate::app::App;
-use std::time::{Duration, Instant};
+use crate::control::{PidController, PidParameter};
+use crate::decay::RECENT_WORK_WINDOW;
+use crate::fuel::Pellet;
+use crate::game::{Game, SAFE_BAND};
+use crate::reactor::{ReactorPhase, Species, TemperatureSource, HISTORY_SIZE};
+use crate::turbine::GENERATOR_RATING_MW;
+use std::collections::HashMap;
+use std::time::Duration;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     // Create the layout
@@ -64,18 +71,56 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Draw the reactor
     draw_reactor(f, app, left_layout[0]);
     
-    // Draw temperature history
-    draw_temperature_chart(f, app, left_layout[1]);
+    // Draw temperature history beside the turbine hall, kernel activity,
+    // storage and stalls
+    let bottom_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(26),  // Temperature history
+            Constraint::Percentage(38),  // Turbine, generator and kernel activity
+            Constraint::Percentage(36),  // Disks, filesystems and pressure stalls
+        ])
+        .split(left_layout[1]);
+    draw_temperature_chart(f, app, bottom_layout[0]);
+    
+    let turbine_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),     // Turbine and generator
+            Constraint::Length(6),  // Load, swap, interrupts and faults
+        ])
+        .split(bottom_layout[1]);
+    draw_turbine(f, app, turbine_layout[0]);
+    draw_kernel(f, app, turbine_layout[1]);
+    
+    let storage_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),     // Disks and filesystems
+            Constraint::Length(6),  // Pressure stalls
+        ])
+        .split(bottom_layout[2]);
+    draw_storage(f, app, storage_layout[0]);
+    draw_pressure_stall(f, app, storage_layout[1]);
     
     // Draw system stats
     draw_system_stats(f, app, right_layout[0]);
     
-    // Draw help if enabled, otherwise show status
+    // Draw help if enabled, otherwise whatever is driving the rods, or the status
     if app.show_help {
         draw_help(f, right_layout[1]);
+    } else if let Some(game) = &app.game {
+        draw_game(f, app, game, right_layout[1]);
+    } else if let Some(controller) = &app.reactor.controller {
+        draw_controller(f, app, controller, right_layout[1]);
     } else {
         draw_status(f, app, right_layout[1]);
     }
+    
+    // The scenario menu floats over the reactor
+    if let Some(selected) = app.scenario_menu {
+        draw_scenario_menu(f, app, selected, left_layout[0]);
+    }
 }
 
 // Add nuclear explosion ASCII art
@@ -110,7 +155,7 @@ fn draw_reactor(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(block, area);
 
     // Check if reactor is exploding
-    if app.reactor.is_exploding {
+    if app.reactor.is_exploding() {
         draw_explosion(f, app, area);
         return;
     }
@@ -159,81 +204,78 @@ fn draw_reactor(f: &mut Frame, app: &App, area: Rect) {
                 color: core_color,
             });
             
-            // Draw control rods
-            let rod_length = 0.2;
-            let rod_width = 0.03;
-            let rod_distance = 0.15;
-            
-            // Top rod
-            ctx.draw(&canvas::Rectangle {
-                x: 0.5 - rod_width / 2.0,
-                y: 0.5 - rod_distance - rod_length * app.reactor.rod_position as f64,
-                width: rod_width,
-                height: rod_length * app.reactor.rod_position as f64,
-                color: Color::DarkGray,
-            });
-            
-            // Bottom rod
-            ctx.draw(&canvas::Rectangle {
-                x: 0.5 - rod_width / 2.0,
-                y: 0.5 + rod_distance as f64,
-                width: rod_width,
-                height: rod_length * app.reactor.rod_position as f64,
-                color: Color::DarkGray,
-            });
+            // Draw the fuel lattice: pellets fade as they burn up and flash
+            // when they split
+            let mut pellets_by_color: HashMap<Color, Vec<(f64, f64)>> = HashMap::new();
+            for pellet in &app.reactor.fuel.pellets {
+                pellets_by_color.entry(pellet_color(pellet)).or_default().push((pellet.x as f64, pellet.y as f64));
+            }
+            for (color, coords) in &pellets_by_color {
+                ctx.draw(&canvas::Points { coords, color: *color });
+            }
             
-            // Left rod
-            ctx.draw(&canvas::Rectangle {
-                x: 0.5 - rod_distance - rod_length * app.reactor.rod_position as f64,
-                y: 0.5 - rod_width / 2.0,
-                width: rod_length * app.reactor.rod_position as f64,
-                height: rod_width,
-                color: Color::DarkGray,
-            });
+            // Draw control rods - one per CPU core, spread evenly around the core.
+            // A withdrawn rod (busy core) is pulled out towards the casing.
+            let rod_count = app.reactor.rods.len().max(1);
+            let rod_length = 0.2;
             
-            // Right rod
-            ctx.draw(&canvas::Rectangle {
-                x: 0.5 + rod_distance as f64,
-                y: 0.5 - rod_width / 2.0,
-                width: rod_length * app.reactor.rod_position as f64,
-                height: rod_width,
-                color: Color::DarkGray,
-            });
+            for (i, &position) in app.reactor.rods.iter().enumerate() {
+                let angle = std::f64::consts::TAU * i as f64 / rod_count as f64 + std::f64::consts::FRAC_PI_2;
+                let tip = 0.05 + 0.2 * position as f64;
+                let (dx, dy) = (angle.cos(), angle.sin());
+                
+                ctx.draw(&canvas::Line {
+                    x1: 0.5 + dx * tip,
+                    y1: 0.5 + dy * tip,
+                    x2: 0.5 + dx * (tip + rod_length),
+                    y2: 0.5 + dy * (tip + rod_length),
+                    color: rod_color(position),
+                });
+            }
             
-            // Draw radiation particles with varying colors based on intensity
+            // Draw radiation particles colored by species (neutrons by
+            // intensity), interpolated between simulation steps for smooth motion. At a
+            // terminal's resolution a particle is a single dot, so they are
+            // batched into one point cloud per color.
+            let alpha = app.reactor.interpolation();
+            let mut points_by_color: HashMap<Color, Vec<(f64, f64)>> = HashMap::new();
             for particle in &app.reactor.particles {
+                let (x, y) = particle.render_position(alpha);
                 // Calculate color based on lifetime, energy and CPU load
                 let intensity = app.system_info.cpu_usage / 100.0;
                 let energy_factor = particle.energy;
                 
-                let particle_color = if intensity > 0.7 {
+                let particle_color = if particle.species != Species::Neutron {
+                    species_color(particle.species)
+                } else if intensity > 0.7 {
                     // High CPU: more red/orange particles
-                    let red_intensity = 255.min((200.0 + particle.lifetime as f32 * 2.0) as u8);
+                    let red_intensity = (200.0 + particle.lifetime * 15.0) as u8;
                     let green_value = ((energy_factor * 100.0) as u8).min(150);
                     Color::Rgb(red_intensity, green_value, 0)
                 } else if intensity > 0.4 {
                     // Medium CPU: more orange/yellow particles
-                    Color::Rgb(255, ((energy_factor * 150.0) as u8).min(255), 0)
+                    Color::Rgb(255, (energy_factor * 150.0) as u8, 0)
                 } else {
                     // Low CPU: yellow/green particles
                     Color::LightYellow
                 };
                 
-                // Draw particles slightly larger if they're more energetic
-                let display_radius = (0.005 + (particle.energy * 0.005)) as f64;
-                
-                ctx.draw(&canvas::Circle {
-                    x: particle.x as f64,
-                    y: particle.y as f64,
-                    radius: display_radius,
-                    color: particle_color,
-                });
+                points_by_color.entry(particle_color).or_default().push((x as f64, y as f64));
+            }
+            for (color, coords) in &points_by_color {
+                ctx.draw(&canvas::Points { coords, color: *color });
             }
             
             // Show particle count and collision info
-            if app.reactor.particles.len() > 0 {
-                let count_text = format!("Particles: {}", app.reactor.particles.len());
-                ctx.print(0.02, 0.02, count_text);
+            if !app.reactor.particles.is_empty() {
+                let mut count_text = vec![Span::raw(format!("Particles: {}", app.reactor.particles.len()))];
+                for (species, count) in Species::ALL.into_iter().zip(app.reactor.species_counts()) {
+                    count_text.push(Span::styled(
+                        format!(" {}{}", species.symbol(), count),
+                        Style::default().fg(species_color(species)),
+                    ));
+                }
+                ctx.print(0.02, 0.02, Line::from(count_text));
                 
                 let collision_text = format!("Collisions: {}", app.reactor.collisions());
                 ctx.print(0.02, 0.04, collision_text);
@@ -265,7 +307,7 @@ fn draw_explosion(f: &mut Frame, app: &App, area: Rect) {
     
     // Create paragraphs with different styles based on animation frame
     let explosion_frame = app.reactor.explosion_frame;
-    let style = if explosion_frame % 2 == 0 {
+    let style = if explosion_frame.is_multiple_of(2) {
         Style::default().add_modifier(Modifier::BOLD)
     } else {
         Style::default()
@@ -289,7 +331,7 @@ fn draw_explosion(f: &mut Frame, app: &App, area: Rect) {
     ));
     text.push(Line::from(
         Span::styled(
-            format!("Radiation level: EXTREME"),
+            "Radiation level: EXTREME",
             Style::default().fg(Color::LightRed)
         )
     ));
@@ -369,6 +411,11 @@ fn draw_system_stats(f: &mut Frame, app: &App, area: Rect) {
             Constraint::Length(2),
             Constraint::Length(1),
             Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(2),
             Constraint::Min(0),
         ])
         .split(inner);
@@ -404,13 +451,18 @@ fn draw_system_stats(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(rad_gauge, chunks[5]);
     
     // Core Temperature
-    let temp_text = Paragraph::new(format!("Core Temperature: {:.1}°C", app.reactor.core_temperature));
+    let temp_source = match (app.reactor.temperature_source, app.system_info.cpu_temp) {
+        (TemperatureSource::Measured, Some(_)) => "measured",
+        (TemperatureSource::Measured, None) => "model, no sensor",
+        (TemperatureSource::Model, _) => "model",
+    };
+    let temp_text = Paragraph::new(format!("Core Temperature: {:.1}°C ({})", app.reactor.core_temperature, temp_source));
     f.render_widget(temp_text, chunks[6]);
     
-    let temp_percent = ((app.reactor.core_temperature - 220.0) / 780.0 * 100.0).max(0.0).min(100.0);
+    let temp_percent = ((app.reactor.core_temperature - 220.0) / 780.0 * 100.0).clamp(0.0, 100.0);
     let temp_gauge = Gauge::default()
         .block(Block::default())
-        .gauge_style(Style::default().fg(gauge_color(temp_percent as f32)))
+        .gauge_style(Style::default().fg(gauge_color(temp_percent)))
         .percent(safe_percentage(temp_percent));
     f.render_widget(temp_gauge, chunks[7]);
     
@@ -424,14 +476,69 @@ fn draw_system_stats(f: &mut Frame, app: &App, area: Rect) {
         .percent(safe_percentage(app.reactor.coolant_level));
     f.render_widget(cool_gauge, chunks[9]);
     
+    // Makeup water, drained by a filling disk, tops the coolant up
+    let reservoir = app.reactor.coolant.reservoir;
+    let reservoir_text = Paragraph::new(format!(
+        "Makeup Reservoir: {:.0}% (disk {:.0}% full)",
+        reservoir, app.system_info.filesystem_usage() * 100.0
+    )).style(Style::default().fg(gauge_color(100.0 - reservoir)));
+    f.render_widget(reservoir_text, chunks[10]);
+    
+    // Primary coolant pumps; starved pumps show as a short, hot bar
+    let coolant = &app.reactor.coolant;
+    let flow_percent = coolant.pump_flow * 100.0;
+    let flow_text = Paragraph::new(format!(
+        "Pump Flow ({}): {:.0} kPa, primary {:.0}°C",
+        coolant.drive, coolant.pressure, coolant.primary_temperature
+    ));
+    f.render_widget(flow_text, chunks[11]);
+    
+    let flow_gauge = Gauge::default()
+        .block(Block::default())
+        .gauge_style(Style::default().fg(gauge_color(100.0 - flow_percent)))
+        .percent(safe_percentage(flow_percent));
+    f.render_widget(flow_gauge, chunks[12]);
+    
+    // Secondary loop: how much steam reaches the turbine
+    let steam_percent = coolant.steam_output * 100.0;
+    let steam_text = Paragraph::new(format!(
+        "Steam Generator: secondary {:.0}°C",
+        coolant.secondary_temperature
+    ));
+    f.render_widget(steam_text, chunks[13]);
+    
+    let steam_gauge = Gauge::default()
+        .block(Block::default())
+        .gauge_style(Style::default().fg(gauge_color(steam_percent)))
+        .percent(safe_percentage(steam_percent));
+    f.render_widget(steam_gauge, chunks[14]);
+    
     // Reactor Status
     let status_style = Style::default()
         .fg(reactor_status_color(&app.reactor_status))
         .add_modifier(Modifier::BOLD);
     
-    let status_text = Paragraph::new(format!("STATUS: {}", app.reactor_status))
-        .style(status_style);
-    f.render_widget(status_text, chunks[10]);
+    let products = &app.reactor.products;
+    let mut lines = vec![
+        Line::from(format!("STATUS: {}", app.reactor_status)).style(status_style),
+        // Reactor physics model and what it reports
+        Line::from(format!(
+            "Model: {}  Power: {:.0}%",
+            app.reactor.model_kind(), app.reactor.power * 100.0
+        )),
+        // Fission products: how hard the machine has been worked lately
+        Line::from(format!(
+            "Decay heat: {:.1}%  Xenon: {:.0}%",
+            products.decay_heat() * 100.0, products.xenon() * 100.0
+        )),
+        Line::from(format!(
+            "Worked: {:.0} CPU·s in {:.0} min ({:.0} total)",
+            products.recent_cpu_seconds, RECENT_WORK_WINDOW / 60.0, products.cpu_seconds
+        )),
+    ];
+
+    lines.extend(app.reactor.model_details().into_iter().map(Line::from));
+    f.render_widget(Paragraph::new(lines), chunks[15]);
 }
 
 fn draw_help(f: &mut Frame, area: Rect) {
@@ -440,17 +547,31 @@ fn draw_help(f: &mut Frame, area: Rect) {
         Line::from("q - Quit"),
         Line::from("p - Pause/Resume monitoring"),
         Line::from("h - Toggle help screen"),
-        Line::from("s - Toggle CPU stress test/simulation"),
+        Line::from("s - Start/stop the stress test: real load on this machine"),
+        Line::from("t - Toggle measured/modelled core temperature"),
+        Line::from("m - Switch reactor model (arcade / point kinetics)"),
+        Line::from("x - SCRAM: drop all rods and cool the core"),
+        Line::from("r - Restart the reactor with a fresh core"),
+        Line::from("f - Refuel: replace burnt-up pellets with fresh fuel"),
+        Line::from("g - Operator mode: run the rods and pumps yourself"),
+        Line::from("Operator: ↑/↓ rods, 1-4 pick a bank (0 all), [/] pumps"),
+        Line::from("c - PID controller: rods hold the core temperature"),
+        Line::from("Controller: Tab pick a setting, ,/. adjust it"),
+        Line::from("l - Load scenarios menu; b - cycle override/add/max blend"),
+        Line::from("Replay: space pause, ←/→ seek 5s, Home restart, +/- speed"),
         Line::from(""),
         Line::from("About:"),
         Line::from("This application visualizes your system load as a nuclear reactor."),
         Line::from("Higher CPU usage = more unstable reactor with higher radiation."),
-        Line::from("The control rods move based on CPU usage and temperature increases."),
+        Line::from("Each CPU core drives its own control rod around the core."),
         Line::from(""),
         Line::from("Physics:"),
         Line::from("• Particles bounce off walls and each other"),
-        Line::from("• When particles collide, they can create new particles"),
-        Line::from("• Higher CPU = more particles and more energetic collisions"),
+        Line::from("• A neutron that hits a fuel pellet can split it into more neutrons"),
+        Line::from("• Higher CPU = more neutrons and more energetic collisions"),
+        Line::from("• α disk I/O (absorbed at the wall), β context switches,"),
+        Line::from("  γ page faults (pass through the casing)"),
+        Line::from("• Network traffic spins the turbine; SCRAM trips it"),
     ];
     
     let block = Block::default()
@@ -464,6 +585,212 @@ fn draw_help(f: &mut Frame, area: Rect) {
     f.render_widget(help, area);
 }
 
+fn draw_game(f: &mut Frame, app: &App, game: &Game, area: Rect) {
+    let mut lines = vec![Line::from(format!(
+        "Difficulty: {}  Score: {:.0}  Best: {}",
+        game.difficulty,
+        game.score,
+        game.high_scores.best().map_or(0, |best| best.score)
+    ))];
+    
+    let stability = app.reactor.stability();
+    let band = format!(
+        "Stability {:.0} (safe {:.0}-{:.0})  In band: {}",
+        stability, SAFE_BAND.start(), SAFE_BAND.end(),
+        format_clock(Duration::from_secs_f32(game.seconds_in_band))
+    );
+    let band_color = if Game::in_band(&app.reactor) { Color::Green } else { Color::LightRed };
+    lines.push(Line::from(band).style(Style::default().fg(band_color)));
+    
+    if let Some(operator) = &app.reactor.operator {
+        let banks = app.reactor.rods.len().clamp(1, operator.banks.len());
+        let mut spans = vec![Span::raw("Rods:")];
+        for (bank, position) in operator.banks[..banks].iter().enumerate() {
+            let mut style = Style::default().fg(rod_color(*position));
+            if operator.selected.is_none_or(|selected| selected == bank) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(format!("{}:{:.0}%", bank + 1, position * 100.0), style));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(format!(
+            "Pumps: {:.0}% demand, {:.0}% flow",
+            operator.pump_demand * 100.0, app.reactor.coolant.pump_flow * 100.0
+        )));
+        lines.push(Line::from(format!(
+            "Load disturbance: +{:.0}% rod worth at {:.0}% CPU",
+            operator.disturbance(app.system_info.cpu_usage / 100.0) * 100.0, app.system_info.cpu_usage
+        )));
+    }
+    lines.push(Line::from("↑/↓ rods  1-4 bank (0 all)  [/] pumps  g end game")
+        .style(Style::default().fg(Color::DarkGray)));
+    
+    if let Some(result) = game.last_result {
+        let placing = match result.rank {
+            Some(rank) => format!("#{} in the high scores", rank + 1),
+            None => "not a high score".to_string(),
+        };
+        lines.push(Line::from(format!("Last game: {} ({})", result.score, placing)));
+    }
+    if let Some(notice) = &game.notice {
+        lines.push(Line::from(notice.as_str()).style(Style::default().fg(Color::Yellow)));
+    }
+    
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!("High scores ({}):", game.high_scores.path().display())));
+    for (rank, entry) in game.high_scores.entries.iter().enumerate() {
+        lines.push(Line::from(format!(
+            "{:>2}. {:>6}  {:<6} {} in band",
+            rank + 1, entry.score, entry.difficulty.to_string(),
+            format_clock(Duration::from_secs_f32(entry.seconds_in_band))
+        )));
+    }
+    
+    let block = Block::default()
+        .title("Operator")
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+fn draw_controller(f: &mut Frame, app: &App, controller: &PidController, area: Rect) {
+    let terms = controller.terms;
+    let tuning = controller.tuning;
+    let mut lines = vec![Line::from(format!(
+        "Setpoint: {:.0}°C  Core: {:.0}°C  Error: {:+.1}°C",
+        tuning.setpoint, app.reactor.core_temperature, terms.error
+    ))];
+    
+    // Contributions in rod travel, so they add up to where the rods are sent
+    lines.push(Line::from(vec![
+        Span::raw("P "),
+        Span::styled(format!("{:+.3}", terms.proportional), Style::default().fg(Color::LightCyan)),
+        Span::raw("  I "),
+        Span::styled(format!("{:+.3}", terms.integral), Style::default().fg(Color::LightMagenta)),
+        Span::raw("  D "),
+        Span::styled(format!("{:+.3}", terms.derivative), Style::default().fg(Color::LightYellow)),
+        Span::raw(format!("  = {:.3}", terms.demand)),
+    ]));
+    lines.push(Line::from(format!(
+        "Rods: {:.0}% withdrawn (asked {:.0}%)",
+        terms.output * 100.0, terms.demand.clamp(0.0, 1.0) * 100.0
+    )));
+    
+    let (state, color) = if app.reactor.phase != ReactorPhase::Operating {
+        ("TRACKING: following the SCRAMmed rods", Color::Cyan)
+    } else if terms.saturated && terms.demand < 0.0 {
+        ("SATURATED: rods fully inserted", Color::LightRed)
+    } else if terms.saturated {
+        ("SATURATED: rods fully withdrawn", Color::LightRed)
+    } else if terms.rate_limited {
+        ("RATE LIMITED: rods at full speed", Color::Yellow)
+    } else {
+        ("In control", Color::Green)
+    };
+    lines.push(Line::from(state).style(Style::default().fg(color)));
+    
+    lines.push(Line::from(format!(
+        "Load disturbance: +{:.0}% rod worth at {:.0}% CPU",
+        app.reactor.load_disturbance(app.system_info.cpu_usage / 100.0) * 100.0, app.system_info.cpu_usage
+    )));
+    
+    // The setting being tuned is highlighted
+    lines.push(Line::from(""));
+    let rows: [&[PidParameter]; 2] = [
+        &[PidParameter::Setpoint, PidParameter::RateLimit],
+        &[PidParameter::Kp, PidParameter::Ki, PidParameter::Kd],
+    ];
+    for row in rows {
+        let mut spans = Vec::new();
+        for &parameter in row {
+            let value = parameter.value(&tuning);
+            let text = match parameter {
+                PidParameter::Setpoint => format!("{} {:.0}°C", parameter, value),
+                PidParameter::RateLimit => format!("{} {:.2}/s", parameter, value),
+                _ => format!("{} {:.4}", parameter, value),
+            };
+            let style = if parameter == app.pid_parameter {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(text, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from("Tab pick a setting  ,/. adjust  c back to the load")
+        .style(Style::default().fg(Color::DarkGray)));
+    
+    let block = Block::default()
+        .title("PID Controller")
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+fn draw_scenario_menu(f: &mut Frame, app: &App, selected: usize, area: Rect) {
+    let playing = app.scenario.as_ref().map(|player| &player.scenario);
+    let mut lines = Vec::new();
+    let entries = std::iter::once(("Real load only", "No scenario")).chain(
+        app.scenarios.iter().map(|s| (s.name.as_str(), s.description.as_str())),
+    );
+    for (index, (name, description)) in entries.enumerate() {
+        let is_playing = match index.checked_sub(1) {
+            Some(i) => playing == Some(&app.scenarios[i]),
+            None => playing.is_none(),
+        };
+        let mut style = Style::default();
+        if is_playing {
+            style = style.fg(Color::LightMagenta);
+        }
+        if index == selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} {}", if is_playing { "▶" } else { " " }, name), style),
+            Span::styled(format!("  {}", description), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    for error in &app.scenario_errors {
+        lines.push(Line::from(error.as_str()).style(Style::default().fg(Color::Yellow)));
+    }
+    
+    lines.push(Line::from(""));
+    let blend = app.scenario.as_ref().map_or("-".to_string(), |player| player.blend.to_string());
+    lines.push(Line::from(format!("↑/↓ choose  Enter play  b blend: {}  Esc close", blend))
+        .style(Style::default().fg(Color::Gray)));
+    
+    // Centred, as tall as it needs to be
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = area.width.saturating_sub(4);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    
+    let block = Block::default()
+        .title("Load Scenarios")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White).bg(Color::Black));
+    
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
 fn draw_status(f: &mut Frame, app: &App, area: Rect) {
     let mut status_text = vec![
         Line::from("Press 'h' to see controls"),
@@ -478,26 +805,128 @@ fn draw_status(f: &mut Frame, app: &App, area: Rect) {
         status_text.push(Line::from("Active monitoring"));
     }
     
+    if let Some(playback) = app.playback() {
+        let state = if playback.is_paused() {
+            "paused"
+        } else if playback.is_finished() {
+            "end"
+        } else {
+            "playing"
+        };
+        status_text.push(Line::from(format!(
+            "▶ REPLAY {} / {} at {}x ({})",
+            format_clock(playback.position()),
+            format_clock(playback.duration()),
+            playback.speed(),
+            state
+        )).style(Style::default().fg(Color::LightCyan)));
+    }
+    
+    if let Some(source_status) = app.source_status() {
+        status_text.push(Line::from(source_status).style(Style::default().fg(Color::LightRed)));
+    }
+    
+    // Never invent a temperature: say so when the hardware has no sensor
+    match (app.system_info.cpu_temp, &app.system_info.cpu_temp_label) {
+        (Some(temp), Some(label)) => {
+            status_text.push(Line::from(format!("CPU sensor: {:.1}°C ({})", temp, label)));
+        }
+        _ => {
+            status_text.push(Line::from("CPU sensor: NO TEMPERATURE SENSOR FOUND")
+                .style(Style::default().fg(Color::Yellow)));
+        }
+    }
+    
+    // In cgroup mode the figures are the cgroup's, against its own limits
+    if let Some(cgroup) = &app.system_info.cgroup {
+        status_text.push(Line::from(format!(
+            "cgroup {}: {:.1} CPUs, throttled {:.0}%",
+            cgroup.path, cgroup.cpu_limit, cgroup.throttled * 100.0
+        )).style(Style::default().fg(Color::LightCyan)));
+        let oom_style = if cgroup.oom_kills > 0 { Style::default().fg(Color::LightRed) } else { Style::default() };
+        status_text.push(Line::from(format!(
+            "  memory {} of {}, OOM kills: {}",
+            format_bytes(cgroup.memory_current as f32), format_bytes(cgroup.memory_limit as f32), cgroup.oom_kills
+        )).style(oom_style));
+    }
+    
     status_text.push(Line::from(""));
-    status_text.push(Line::from(format!("Press 's' to simulate CPU load")));
+    status_text.push(Line::from("Press 's' to stress the CPU, 'l' for load scenarios"));
+    if let Some(stress) = &app.stress {
+        let config = stress.config;
+        let mut workers = format!("{} CPU", stress.cpu_workers);
+        if config.memory_workers > 0 {
+            workers += &format!(" + {} memory", config.memory_workers);
+        }
+        if config.alloc_workers > 0 {
+            workers += &format!(" + {} alloc", config.alloc_workers);
+        }
+        let limit = config.duration.map_or(String::new(), |d| format!(" / {}", format_clock(d)));
+        status_text.push(Line::from(format!(
+            "STRESS: {} workers at {:.0}% for {}{}",
+            workers, config.utilization, format_clock(stress.elapsed()), limit
+        )).style(Style::default().fg(Color::LightRed)));
+    }
+    if let Some(player) = &app.scenario {
+        status_text.push(Line::from(format!(
+            "Scenario: {} ({}) {} / {} at {:.0}%",
+            player.scenario.name,
+            player.blend,
+            format_clock(Duration::from_secs_f32(player.elapsed)),
+            format_clock(Duration::from_secs_f32(player.scenario.duration())),
+            player.level.unwrap_or(0.0)
+        )).style(Style::default().fg(Color::LightMagenta)));
+    }
+    // Point out the busiest core so a single pegged core isn't lost in the average
+    if let Some((core, position)) = app.reactor.rods
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+    {
+        status_text.push(Line::from(format!(
+            "Control rods: {} (most withdrawn: core {} at {:.0}%)",
+            app.reactor.rods.len(), core, position * 100.0
+        )));
+    }
     status_text.push(Line::from(format!("Active particles: {}", app.reactor.particles.len())));
     status_text.push(Line::from(format!("Recent collisions: {}", app.reactor.collisions())));
     status_text.push(Line::from(format!("Total collisions: {}", app.reactor.total_collisions)));
+    status_text.push(Line::from(format!(
+        "Meltdowns: {}  SCRAMs: {}  Refuels: {}",
+        app.reactor.meltdowns, app.reactor.scrams, app.reactor.refuels
+    )));
+    status_text.push(Line::from(format!(
+        "Fuel: {:.0}% left, {} fissions",
+        app.reactor.fuel.fuel_remaining() * 100.0, app.reactor.fuel.fissions
+    )));
+    status_text.push(Line::from(format!("Seed: {}", app.reactor.seed())));
     
-    // Add meltdown warning if approaching critical mass
-    if app.reactor.total_collisions > 50 && !app.reactor.is_exploding {
-        // Check if we're close to or over 100 collisions
-        let remaining = if app.reactor.total_collisions >= 100 {
-            0
-        } else {
-            100 - app.reactor.total_collisions
-        };
-        let warning = format!("⚠️ WARNING: {} collisions until meltdown!", remaining);
-        status_text.push(Line::from(warning).style(Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)));
+    let criteria = app.reactor.meltdown;
+    status_text.push(Line::from(format!(
+        "Collision rate: {:.0}/s over {:.0}s (critical at {:.0}/s)",
+        app.reactor.collision_rate(), criteria.window, criteria.collision_rate
+    )));
+    
+    // Add meltdown warning if the core is heading for criticality
+    match app.reactor.time_to_criticality() {
+        Some(seconds) => {
+            let warning = format!("⚠️ WARNING: criticality in {:.1}s!", seconds);
+            status_text.push(Line::from(warning).style(Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)));
+        }
+        None if app.reactor.phase == ReactorPhase::Operating => {
+            status_text.push(Line::from("Time to criticality: stable"));
+        }
+        None => {}
+    }
+    
+    if app.reactor.phase == ReactorPhase::Cooldown {
+        status_text.push(Line::from(""));
+        status_text.push(Line::from("SCRAM: rods inserted, core cooling down ('r' to restart now)")
+            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
     }
     
     // If exploding, add critical warning
-    if app.reactor.is_exploding {
+    if app.reactor.is_exploding() {
         status_text.push(Line::from(""));
         status_text.push(Line::from("⚠️ CRITICAL: MELTDOWN IN PROGRESS ⚠️")
             .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK)));
@@ -516,14 +945,222 @@ fn draw_status(f: &mut Frame, app: &App, area: Rect) {
 
 // Helper functions
 fn inner_area(area: Rect) -> Rect {
-    let inner = Layout::default()
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Percentage(100),
         ])
         .margin(1)
-        .split(area)[0];
-    inner
+        .split(area)[0]
+}
+
+fn draw_turbine(f: &mut Frame, app: &App, area: Rect) {
+    const ROTOR: [&str; 4] = ["|", "/", "-", "\\"];
+    let turbine = &app.reactor.turbine;
+    let rotor = ROTOR[(turbine.angle * ROTOR.len() as f32) as usize % ROTOR.len()];
+    
+    let mut lines = vec![];
+    let mut header = vec![Span::styled(
+        format!("Turbine {} {:>4.0} rpm", rotor, turbine.rpm()),
+        Style::default().fg(Color::LightCyan),
+    )];
+    if turbine.tripped {
+        header.push(Span::styled("  TRIPPED", Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)));
+    }
+    lines.push(Line::from(header));
+    
+    let load = turbine.output_mw / GENERATOR_RATING_MW * 100.0;
+    lines.push(Line::from(format!("Generator: {:.0} MW ({:.0}%)", turbine.output_mw, load))
+        .style(Style::default().fg(gauge_color(load))));
+    
+    let network = &app.system_info.network;
+    let (rx, tx) = network.iter().fold((0.0, 0.0), |(rx, tx), i| (rx + i.rx_rate, tx + i.tx_rate));
+    lines.push(Line::from(format!("Network: ↓{}/s ↑{}/s", format_bytes(rx), format_bytes(tx))));
+    
+    // One line for the traffic on each interface, one for its packets, and
+    // one for its faults if it has any
+    for interface in network {
+        lines.push(Line::from(format!(
+            "{} ↓{}/s ↑{}/s",
+            interface.name, format_bytes(interface.rx_rate), format_bytes(interface.tx_rate)
+        )));
+        lines.push(Line::from(format!(
+            "  {:.0}/{:.0} pkt/s",
+            interface.rx_packet_rate, interface.tx_packet_rate
+        )).style(Style::default().fg(Color::DarkGray)));
+        let faults = interface.rx_errors + interface.tx_errors + interface.rx_drops + interface.tx_drops;
+        if faults > 0 {
+            lines.push(Line::from(format!(
+                "  err {}/{} drop {}/{}",
+                interface.rx_errors, interface.tx_errors, interface.rx_drops, interface.tx_drops
+            )).style(Style::default().fg(Color::Yellow)));
+        }
+    }
+    if network.is_empty() {
+        lines.push(Line::from("No network interfaces watched").style(Style::default().fg(Color::DarkGray)));
+    }
+    
+    let block = Block::default()
+        .title("Turbine Hall")
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+fn draw_storage(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines = vec![];
+    
+    // Traffic on each disk, then how hard it is working
+    for disk in &app.system_info.disks {
+        lines.push(Line::from(format!(
+            "{} R{}/s W{}/s",
+            disk.name, format_bytes(disk.read_rate), format_bytes(disk.write_rate)
+        )));
+        let busy = disk.utilization * 100.0;
+        lines.push(Line::from(format!("  {:.0} IOPS {:.0}% busy", disk.iops(), busy))
+            .style(Style::default().fg(gauge_color(busy))));
+    }
+    if app.system_info.disks.is_empty() {
+        lines.push(Line::from("No disk statistics").style(Style::default().fg(Color::DarkGray)));
+    }
+    
+    // Space left on each filesystem, the fullest first
+    let mut filesystems: Vec<_> = app.system_info.filesystems.iter().collect();
+    filesystems.sort_by(|a, b| b.usage().total_cmp(&a.usage()));
+    for filesystem in filesystems {
+        let used = filesystem.usage() * 100.0;
+        lines.push(Line::from(format!(
+            "{} {:.0}% {} free",
+            filesystem.mount_point, used, format_bytes(filesystem.available_bytes as f32)
+        )).style(Style::default().fg(gauge_color(used))));
+    }
+    
+    let block = Block::default()
+        .title("Storage")
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+fn draw_kernel(f: &mut Frame, app: &App, area: Rect) {
+    let info = &app.system_info;
+    
+    // A run queue longer than there are cores, and swapping, shake the core,
+    // so they stand out
+    let cores = info.core_usage.len().max(1);
+    let [one, five, fifteen] = info.load_average;
+    let load_style = if one > cores as f32 { Style::default().fg(Color::LightRed) } else { Style::default() };
+    let swap_rate = info.swap_in_rate + info.swap_out_rate;
+    let swap_style = if swap_rate > 0.0 { Style::default().fg(Color::LightRed) } else { Style::default() };
+    
+    let lines = vec![
+        Line::from(format!("Load {:.2} {:.2} {:.2}", one, five, fifteen)).style(load_style),
+        Line::from(format!("Swap {:.0}%, {:.0} pages/s", info.swap_usage, swap_rate)).style(swap_style),
+        Line::from(format!("Major faults {:.0}/s", info.major_fault_rate)).style(swap_style),
+        Line::from(format!("Intr {:.0}/s  Ctx {:.0}/s", info.interrupt_rate, info.context_switch_rate)),
+    ];
+    
+    let block = Block::default()
+        .title(format!("Kernel ({} CPUs)", cores))
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+fn draw_pressure_stall(f: &mut Frame, app: &App, area: Rect) {
+    let pressure = &app.system_info.pressure;
+    let mut lines = vec![];
+    
+    // "some" over each window, and "full" over the last 10 s
+    if pressure.stall().is_some() {
+        lines.push(Line::from(format!("{:<4}{:>6}{:>6}{:>6}{:>6}", "", "10s", "60s", "300s", "full"))
+            .style(Style::default().fg(Color::DarkGray)));
+        for (name, stall) in pressure.resources() {
+            let Some(stall) = stall else {
+                lines.push(Line::from(format!("{:<4}{:>6}", name, "-")));
+                continue;
+            };
+            let full = stall.full.map_or("-".to_string(), |full| format!("{:.1}", full.avg10));
+            lines.push(Line::from(format!(
+                "{:<4}{:>6.1}{:>6.1}{:>6.1}{:>6}",
+                name, stall.some.avg10, stall.some.avg60, stall.some.avg300, full
+            )).style(Style::default().fg(gauge_color(stall.stall() * 4.0))));
+        }
+    } else {
+        lines.push(Line::from("Not reported by this kernel").style(Style::default().fg(Color::DarkGray)));
+        lines.push(Line::from("Pressure follows temperature").style(Style::default().fg(Color::DarkGray)));
+    }
+    
+    let block = Block::default()
+        .title("Pressure Stall %")
+        .borders(Borders::ALL);
+    
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::Gray));
+    
+    f.render_widget(panel, area);
+}
+
+// Bytes with a binary unit, e.g. "1.2M"
+fn format_bytes(bytes: f32) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = bytes.max(0.0);
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 || value >= 100.0 {
+        format!("{:.0}{}", value, UNITS[unit])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+fn format_clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn rod_color(position: f32) -> Color {
+    match position {
+        p if p > 0.9 => Color::Red,
+        p if p > 0.6 => Color::LightYellow,
+        _ => Color::DarkGray,
+    }
+}
+
+fn pellet_color(pellet: &Pellet) -> Color {
+    match pellet.fuel {
+        _ if pellet.is_flashing() => Color::White,
+        _ if pellet.is_spent() => Color::DarkGray,
+        f if f > 0.66 => Color::LightGreen,
+        f if f > 0.33 => Color::Green,
+        _ => Color::Gray,
+    }
+}
+
+// Neutrons are drawn by intensity; this is their color in the legend
+fn species_color(species: Species) -> Color {
+    match species {
+        Species::Alpha => Color::Magenta,
+        Species::Beta => Color::LightBlue,
+        Species::Gamma => Color::White,
+        Species::Neutron => Color::LightYellow,
+    }
 }
 
 fn gauge_color(value: f32) -> Color {
@@ -548,9 +1185,7 @@ fn reactor_status_color(status: &str) -> Color {
     }
 }
 
-const HISTORY_SIZE: usize = 30;
-
 // Add this helper function to ensure percentages stay within bounds
 fn safe_percentage(value: f32) -> u16 {
-    (value.max(0.0).min(100.0) as u16)
-} 
\ No newline at end of file
+    value.clamp(0.0, 100.0) as u16
+}
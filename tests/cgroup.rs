@@ -0,0 +1,99 @@
+// cgroup v2 mode, checked against a fake hierarchy under tests/fixtures/cgroup
+
+use nuclear_monitor::cgroup::{CgroupFs, CgroupReading, CgroupStats};
+use nuclear_monitor::procfs::ProcFs;
+use nuclear_monitor::reactor::{Reactor, ReactorInput, ReactorPhase};
+use std::path::Path;
+
+const ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/cgroup");
+
+fn fixture_procfs() -> ProcFs {
+    ProcFs::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc"))
+}
+
+#[test]
+fn reads_a_limited_cgroup() {
+    let reading = CgroupFs::new(ROOT, "system.slice/app.service").read().expect("fixture cgroup");
+    assert_eq!(reading, CgroupReading {
+        cpu_usage_usec: 60_000_000,
+        throttled_usec: 2_500_000,
+        cpu_limit: Some(1.5),
+        memory_current: Some(256 << 20),
+        memory_max: Some(512 << 20),
+        oom_kills: 2,
+        io_read_bytes: 3 << 20,
+        io_written_bytes: 4 << 20,
+    });
+}
+
+#[test]
+fn unlimited_and_root_cgroups_have_no_limits() {
+    let unlimited = CgroupFs::new(ROOT, "/unlimited").read().expect("fixture cgroup");
+    assert_eq!((unlimited.cpu_limit, unlimited.memory_max), (None, None));
+
+    // The root has no cpu.max, memory files or io.stat at all
+    let root = CgroupFs::new(ROOT, "/").read().expect("fixture root cgroup");
+    assert_eq!((root.cpu_limit, root.memory_current, root.oom_kills), (None, None, 0));
+}
+
+#[test]
+fn resolves_this_process_cgroup_or_fails() {
+    let own = CgroupFs::resolve(ROOT, None, &fixture_procfs()).expect("fixture /proc/self/cgroup");
+    assert_eq!(own.path(), Path::new("/system.slice/app.service"));
+
+    assert!(CgroupFs::resolve(ROOT, Some(Path::new("missing.slice")), &fixture_procfs()).is_err());
+    assert!(CgroupFs::resolve(ROOT, None, &ProcFs::new("/nonexistent")).is_err());
+}
+
+#[test]
+fn usage_is_measured_against_the_cgroups_limits() {
+    let earlier = CgroupReading {
+        cpu_limit: Some(1.5),
+        memory_max: Some(512 << 20),
+        ..CgroupReading::default()
+    };
+    // 1.5 CPU-seconds over 2 s is half of a 1.5-CPU quota
+    let now = CgroupReading {
+        cpu_usage_usec: 1_500_000,
+        throttled_usec: 200_000,
+        memory_current: Some(128 << 20),
+        io_read_bytes: 2 << 20,
+        ..earlier
+    };
+    let stats = CgroupStats::new(Path::new("/app"), &now, Some(&earlier), 2.0, 16, (8 << 30, 64 << 30));
+    assert_eq!(stats.cpu_usage, 50.0);
+    assert_eq!(stats.throttled, 0.1);
+    assert_eq!(stats.memory_usage, 25.0);
+    assert_eq!(stats.io_read_rate, (1 << 20) as f32);
+
+    // Without limits, the machine's CPUs and memory are the limit
+    let unlimited = CgroupReading { cpu_limit: None, memory_max: None, ..now };
+    let stats = CgroupStats::new(Path::new("/app"), &unlimited, Some(&earlier), 2.0, 16, (8 << 30, 64 << 30));
+    assert_eq!(stats.cpu_limit, 16.0);
+    assert_eq!(stats.memory_limit, 64 << 30);
+
+    // A quota of 64 CPUs on a 4-CPU machine is no limit at all
+    let generous = CgroupReading { cpu_limit: Some(64.0), cpu_usage_usec: 4_000_000, ..now };
+    let stats = CgroupStats::new(Path::new("/app"), &generous, Some(&earlier), 2.0, 4, (8 << 30, 64 << 30));
+    assert_eq!(stats.cpu_limit, 4.0);
+    assert_eq!(stats.cpu_usage, 50.0);
+}
+
+#[test]
+fn a_rising_oom_kill_count_melts_the_core_down() {
+    let mut reactor = Reactor::with_seed(7);
+    let input = |oom_kills| ReactorInput {
+        cpu_load: 10.0,
+        oom_kills: Some(oom_kills),
+        ..ReactorInput::default()
+    };
+
+    // Kills from before monitoring started don't count
+    reactor.step(&input(2));
+    reactor.step(&input(2));
+    assert_eq!(reactor.phase, ReactorPhase::Operating);
+
+    reactor.step(&input(3));
+    assert_eq!(reactor.phase, ReactorPhase::Meltdown);
+    assert_eq!(reactor.meltdowns, 1);
+}
@@ -0,0 +1,41 @@
+// Benchmark-style check that a reactor full of particles still fits in the
+// 30 FPS frame budget. Only an optimized build says anything about that, so
+// it runs under `cargo test --release` and is skipped in debug builds.
+
+use nuclear_monitor::reactor::{Particle, Reactor, ReactorInput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+const PARTICLES: usize = 5000;
+const FRAME_TIME: f32 = 1.0 / 30.0;
+const FRAMES: u32 = 60;
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "the frame budget only holds in release builds")]
+fn five_thousand_particles_fit_the_frame_budget() {
+    let mut reactor = Reactor::with_seed(42);
+    let mut rng = StdRng::seed_from_u64(42);
+    reactor.particles = (0..PARTICLES)
+        .map(|_| Particle::new(rng.random_range(0.05..0.95), rng.random_range(0.05..0.95), 0.5, &mut rng))
+        .collect();
+
+    // A moderate load keeps the reactor out of meltdown, which would stop the simulation
+    let input = ReactorInput {
+        cpu_load: 30.0,
+        core_loads: vec![30.0; 8],
+        ..ReactorInput::default()
+    };
+
+    let started = Instant::now();
+    for _ in 0..FRAMES {
+        reactor.update(FRAME_TIME, &input);
+    }
+    let per_frame = started.elapsed() / FRAMES;
+
+    assert_eq!(reactor.meltdowns, 0, "reactor melted down, nothing was simulated");
+    assert!(reactor.particles.len() >= PARTICLES / 2, "only {} particles left", reactor.particles.len());
+
+    let budget = Duration::from_secs_f32(FRAME_TIME);
+    assert!(per_frame < budget, "{:?} per frame exceeds the {:?} budget", per_frame, budget);
+}
@@ -0,0 +1,94 @@
+// Elastic collisions between particles, with energy standing in for mass
+
+use nuclear_monitor::reactor::Particle;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn particle(x: f32, y: f32, velocity: (f32, f32), energy: f32) -> Particle {
+    let mut particle = Particle::new(x, y, 0.0, &mut StdRng::seed_from_u64(0));
+    (particle.velocity_x, particle.velocity_y) = velocity;
+    particle.energy = energy;
+    particle
+}
+
+fn momentum(a: &Particle, b: &Particle) -> (f32, f32) {
+    (
+        a.energy * a.velocity_x + b.energy * b.velocity_x,
+        a.energy * a.velocity_y + b.energy * b.velocity_y,
+    )
+}
+
+fn kinetic_energy(a: &Particle, b: &Particle) -> f32 {
+    let of = |p: &Particle| 0.5 * p.energy * (p.velocity_x.powi(2) + p.velocity_y.powi(2));
+    of(a) + of(b)
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-4 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[test]
+fn equal_masses_swap_velocities_head_on() {
+    let mut a = particle(0.5, 0.5, (0.2, 0.0), 1.0);
+    let mut b = particle(0.5 + a.radius, 0.5, (-0.1, 0.0), 1.0);
+    assert!(a.collides_with(&b));
+    assert!(a.collide(&mut b));
+    assert!(close(a.velocity_x, -0.1) && close(b.velocity_x, 0.2), "{} {}", a.velocity_x, b.velocity_x);
+    assert!(close(a.velocity_y, 0.0) && close(b.velocity_y, 0.0));
+}
+
+#[test]
+fn momentum_and_energy_are_conserved() {
+    // Oblique impacts between unequal masses, from every side
+    for i in 0..16 {
+        let angle = i as f32 * std::f32::consts::TAU / 16.0;
+        let mut a = particle(0.5, 0.5, (0.3, 0.1), 0.5 + i as f32 * 0.3);
+        let reach = a.radius * 1.5;
+        let mut b = particle(0.5 + reach * angle.cos(), 0.5 + reach * angle.sin(), (-0.2, 0.25), 2.0);
+        let (before_momentum, before_energy) = (momentum(&a, &b), kinetic_energy(&a, &b));
+
+        if a.collide(&mut b) {
+            let after = momentum(&a, &b);
+            assert!(close(after.0, before_momentum.0) && close(after.1, before_momentum.1), "angle {}: momentum {:?} -> {:?}", angle, before_momentum, after);
+            assert!(close(kinetic_energy(&a, &b), before_energy), "angle {}: energy {} -> {}", angle, before_energy, kinetic_energy(&a, &b));
+        } else {
+            // Only a pair that is already moving apart is left alone
+            let closing = (a.velocity_x - b.velocity_x) * angle.cos() + (a.velocity_y - b.velocity_y) * angle.sin();
+            assert!(closing <= 0.0, "angle {}: an approaching pair didn't collide", angle);
+        }
+    }
+}
+
+#[test]
+fn heavier_particles_are_deflected_less() {
+    let mut light = particle(0.5, 0.5, (0.2, 0.0), 0.2);
+    let mut heavy = particle(0.5 + light.radius, 0.5, (0.0, 0.0), 5.0);
+    assert!(light.collide(&mut heavy));
+    // The light one bounces back, the heavy one barely gets going
+    assert!(light.velocity_x < 0.0);
+    assert!(heavy.velocity_x > 0.0 && heavy.velocity_x < 0.1, "{}", heavy.velocity_x);
+}
+
+#[test]
+fn an_overlap_counts_as_one_collision() {
+    let mut a = particle(0.5, 0.5, (0.2, 0.0), 1.0);
+    let mut b = particle(0.5 + a.radius * 0.5, 0.5, (0.0, 0.0), 3.0);
+    assert!(a.collide(&mut b));
+
+    // Pushed apart until they just touch
+    let distance = (b.x - a.x).hypot(b.y - a.y);
+    assert!(distance >= (a.radius + b.radius) * 0.999, "still overlapping at {}", distance);
+
+    // Checking the pair again, before they have moved, isn't another impact
+    let velocities = (a.velocity_x, b.velocity_x);
+    assert!(!a.collide(&mut b));
+    assert_eq!((a.velocity_x, b.velocity_x), velocities);
+}
+
+#[test]
+fn separating_particles_are_left_alone() {
+    let mut a = particle(0.5, 0.5, (-0.2, 0.0), 1.0);
+    let mut b = particle(0.5 + a.radius, 0.5, (0.2, 0.0), 1.0);
+    assert!(!a.collide(&mut b));
+    assert_eq!((a.velocity_x, b.velocity_x), (-0.2, 0.2));
+}
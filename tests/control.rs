@@ -0,0 +1,112 @@
+// Automatic rod control: the PID loop holding the core at its setpoint
+
+use nuclear_monitor::control::{PidController, PidParameter, PidTuning};
+use nuclear_monitor::reactor::{Reactor, ReactorInput, FIXED_TIMESTEP};
+
+fn load(cpu_load: f32) -> ReactorInput {
+    ReactorInput {
+        cpu_load,
+        core_loads: vec![cpu_load; 4],
+        ..ReactorInput::default()
+    }
+}
+
+// Mean core temperature over the last `average` of `seconds`
+fn run(reactor: &mut Reactor, input: &ReactorInput, seconds: f32, average: f32) -> f32 {
+    let steps = (seconds / FIXED_TIMESTEP) as usize;
+    let tail = (average / FIXED_TIMESTEP) as usize;
+    let mut total = 0.0;
+    for step in 0..steps {
+        reactor.step(input);
+        if step >= steps - tail {
+            total += reactor.core_temperature;
+        }
+    }
+    total / tail as f32
+}
+
+#[test]
+fn the_core_settles_at_the_setpoint_whatever_the_load() {
+    let mut rods = Vec::new();
+    for cpu_load in [10.0, 50.0] {
+        let mut reactor = Reactor::with_seed(2);
+        reactor.controller = Some(PidController::new(PidTuning::default(), reactor.rod_position));
+        let temperature = run(&mut reactor, &load(cpu_load), 120.0, 20.0);
+        assert!((temperature - 500.0).abs() < 10.0, "{} °C at {}% load", temperature, cpu_load);
+        rods.push(reactor.rod_position);
+    }
+    // The load is rejected by driving the rods further in
+    assert!(rods[1] < rods[0] - 0.1, "rods at {:?}", rods);
+}
+
+#[test]
+fn a_new_setpoint_is_followed_at_runtime() {
+    let mut reactor = Reactor::with_seed(2);
+    reactor.controller = Some(PidController::new(PidTuning::default(), reactor.rod_position));
+    run(&mut reactor, &load(30.0), 60.0, 1.0);
+
+    let controller = reactor.controller.as_mut().unwrap();
+    PidParameter::Setpoint.adjust(&mut controller.tuning, -10.0);
+    assert_eq!(controller.tuning.setpoint, 400.0);
+    let temperature = run(&mut reactor, &load(30.0), 120.0, 20.0);
+    assert!((temperature - 400.0).abs() < 10.0, "{} °C", temperature);
+}
+
+#[test]
+fn taking_over_leaves_the_rods_where_they_are() {
+    let mut controller = PidController::new(PidTuning::default(), 0.4);
+    assert_eq!(controller.update(500.0, FIXED_TIMESTEP), 0.4);
+    assert_eq!(controller.terms.error, 0.0);
+    assert!(!controller.terms.saturated && !controller.terms.rate_limited);
+}
+
+#[test]
+fn the_rods_are_driven_no_faster_than_the_rate_limit() {
+    let tuning = PidTuning { rate_limit: 0.1, ..PidTuning::default() };
+    let mut controller = PidController::new(tuning, 0.5);
+    let mut position = 0.5;
+    for _ in 0..60 {
+        let output = controller.update(900.0, FIXED_TIMESTEP);
+        assert!(position - output <= 0.1 * FIXED_TIMESTEP + 1e-6);
+        position = output;
+    }
+    assert!(controller.terms.rate_limited);
+    assert!(controller.terms.demand < position);
+    assert!((position - 0.4).abs() < 1e-3, "rods at {}", position);
+}
+
+#[test]
+fn the_integral_doesnt_wind_up_against_an_end_stop() {
+    let tuning = PidTuning { rate_limit: 1.0, ..PidTuning::default() };
+    let mut controller = PidController::new(tuning, 0.5);
+    // A core that can't get hot enough keeps the rods pinned all the way out
+    for _ in 0..(120.0 / FIXED_TIMESTEP) as usize {
+        controller.update(300.0, FIXED_TIMESTEP);
+    }
+    assert!(controller.terms.output > 0.999);
+    assert!(controller.terms.saturated);
+    assert!(controller.terms.integral < 1.0, "integral wound up to {}", controller.terms.integral);
+
+    // Once the core overshoots, the rods start going back in straight away
+    controller.update(520.0, FIXED_TIMESTEP);
+    let output = controller.update(520.0, FIXED_TIMESTEP);
+    assert!(output < 1.0, "rods still pinned at {}", output);
+}
+
+#[test]
+fn tuning_stays_in_range() {
+    let mut tuning = PidTuning::default();
+    PidParameter::Setpoint.adjust(&mut tuning, 100.0);
+    assert_eq!(tuning.setpoint, 900.0);
+    PidParameter::Kp.adjust(&mut tuning, -100.0);
+    assert_eq!(tuning.kp, 0.0);
+    PidParameter::RateLimit.adjust(&mut tuning, -100.0);
+    assert_eq!(tuning.rate_limit, 0.05);
+
+    // The parameter keys cycle through them all and back
+    let mut parameter = PidParameter::default();
+    for expected in PidParameter::ALL.iter().cycle().skip(1).take(5) {
+        parameter = parameter.next();
+        assert_eq!(parameter, *expected);
+    }
+}
@@ -0,0 +1,97 @@
+// The coolant loop: pumps driven by memory or disk headroom, heat flowing
+// out to the turbine, and what starving the loop does to the core
+
+use nuclear_monitor::reactor::{Reactor, ReactorInput, FIXED_TIMESTEP};
+use nuclear_monitor::thermal::{CoolantLoop, PumpDrive};
+
+// Step for `seconds` with the core held at a fixed temperature
+fn run(coolant: &mut CoolantLoop, core_temperature: f32, power: f32, flow: f32, seconds: f32) -> f32 {
+    let mut core = core_temperature;
+    for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+        core = coolant.step(core_temperature, power, flow, FIXED_TIMESTEP);
+    }
+    core
+}
+
+#[test]
+fn pumps_follow_the_configured_headroom() {
+    let memory = CoolantLoop::new(PumpDrive::Memory);
+    assert_eq!(memory.target_flow(30.0, 1.0e9), 1.0);
+    assert_eq!(memory.target_flow(75.0, 0.0), 0.5);
+    assert_eq!(memory.target_flow(100.0, 0.0), 0.0);
+
+    let disk = CoolantLoop::new(PumpDrive::DiskIo);
+    assert_eq!(disk.target_flow(100.0, 0.0), 1.0);
+    assert_eq!(disk.target_flow(0.0, 100.0e6), 0.5);
+    assert_eq!(disk.target_flow(0.0, 1.0e9), 0.0);
+}
+
+#[test]
+fn heat_flows_from_the_core_to_the_turbine() {
+    let mut coolant = CoolantLoop::default();
+    let (primary, secondary) = (coolant.primary_temperature, coolant.secondary_temperature);
+    let core = run(&mut coolant, 700.0, 1.0, 1.0, 10.0);
+
+    assert!(coolant.heat_removed > 0.0);
+    assert!(coolant.primary_temperature > primary);
+    assert!(coolant.secondary_temperature > secondary);
+    assert!(coolant.primary_temperature > coolant.secondary_temperature);
+    assert!(coolant.steam_output > 0.0);
+    // Pumping at full flow takes nothing extra out of the core's own model
+    assert_eq!(core, 700.0);
+}
+
+#[test]
+fn a_starved_loop_heats_the_core_and_loses_coolant() {
+    let mut pumped = CoolantLoop::default();
+    let mut starved = CoolantLoop::default();
+    run(&mut pumped, 400.0, 0.0, 1.0, 20.0);
+    let heated = run(&mut starved, 400.0, 0.0, 0.0, 20.0);
+
+    assert!(starved.pump_flow < 0.01);
+    // Even an idle core warms up unpumped
+    assert!(heated > 400.0);
+    assert!(starved.coolant_level < pumped.coolant_level - 20.0, "{} vs {}", starved.coolant_level, pumped.coolant_level);
+    assert!(starved.pressure > pumped.pressure);
+}
+
+#[test]
+fn memory_pressure_starves_a_calm_reactor() {
+    let calm = |memory_usage| ReactorInput {
+        cpu_load: 10.0,
+        core_loads: vec![10.0; 4],
+        memory_usage,
+        ..ReactorInput::default()
+    };
+    let mut roomy = Reactor::with_seed(4);
+    let mut full = Reactor::with_seed(4);
+    for _ in 0..(20.0 / FIXED_TIMESTEP) as usize {
+        roomy.step(&calm(40.0));
+        full.step(&calm(99.0));
+    }
+    assert!(full.coolant.pump_flow < 0.1);
+    assert!(full.core_temperature > roomy.core_temperature + 50.0, "{} vs {}", full.core_temperature, roomy.core_temperature);
+    assert!(full.coolant_level < roomy.coolant_level);
+}
+
+#[test]
+fn storage_and_contention_feed_the_plant() {
+    let mut coolant = CoolantLoop::default();
+    for _ in 0..(60.0 / FIXED_TIMESTEP) as usize {
+        coolant.update_storage(0.0, 0.95, FIXED_TIMESTEP);
+    }
+    // A nearly full filesystem drains most of the makeup water
+    assert!((coolant.reservoir - 20.0).abs() < 5.0, "reservoir at {}", coolant.reservoir);
+
+    let mut quiet = CoolantLoop::default();
+    let mut stalled = CoolantLoop::default();
+    stalled.update_contention(Some(25.0));
+    run(&mut quiet, 300.0, 0.5, 1.0, 1.0);
+    run(&mut stalled, 300.0, 0.5, 1.0, 1.0);
+    assert!((stalled.pressure - (101.3 + 25.0 * 4.0)).abs() < 0.1, "{}", stalled.pressure);
+
+    let mut busy_disk = CoolantLoop::default();
+    busy_disk.update_storage(1.0, 0.0, FIXED_TIMESTEP);
+    run(&mut busy_disk, 300.0, 0.5, 1.0, 1.0);
+    assert!(busy_disk.pressure > quiet.pressure + 50.0);
+}
@@ -0,0 +1,96 @@
+// Fission products: decay heat and xenon, the core's memory of past load
+
+use nuclear_monitor::decay::FissionProducts;
+use nuclear_monitor::reactor::{Reactor, ReactorInput, FIXED_TIMESTEP};
+
+fn run(products: &mut FissionProducts, loads: &[f32], seconds: f32) {
+    for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+        products.update(loads, FIXED_TIMESTEP);
+    }
+}
+
+#[test]
+fn cpu_seconds_add_up_across_cores() {
+    let mut products = FissionProducts::new();
+    run(&mut products, &[50.0; 4], 10.0);
+    assert!((products.cpu_seconds - 20.0).abs() < 0.1, "{}", products.cpu_seconds);
+    assert!(products.recent_cpu_seconds > 19.0);
+}
+
+#[test]
+fn decay_heat_builds_with_sustained_load_and_lingers() {
+    let mut spike = FissionProducts::new();
+    run(&mut spike, &[100.0; 4], 10.0);
+    let mut sustained = FissionProducts::new();
+    run(&mut sustained, &[100.0; 4], 1200.0);
+
+    // Ten minutes and more at full load leave close to the full share
+    assert!(sustained.decay_heat() > spike.decay_heat() * 2.0);
+    assert!((sustained.decay_heat() - 0.12).abs() < 0.01, "{}", sustained.decay_heat());
+
+    // After the load drops it dies away, but slowly
+    let mut previous = sustained.decay_heat();
+    for _ in 0..60 {
+        run(&mut sustained, &[0.0; 4], 1.0);
+        assert!(sustained.decay_heat() < previous);
+        previous = sustained.decay_heat();
+    }
+    assert!(previous > 0.02, "gone after a minute: {}", previous);
+}
+
+#[test]
+fn xenon_peaks_after_the_load_drops() {
+    let mut products = FissionProducts::new();
+    run(&mut products, &[100.0; 4], 600.0);
+    let at_drop = products.xenon();
+    assert!(at_drop > 0.05, "{}", at_drop);
+
+    // Iodine keeps decaying into xenon, and nothing burns it out any more
+    run(&mut products, &[0.0; 4], 120.0);
+    assert!(products.xenon() > at_drop * 1.2, "{} -> {}", at_drop, products.xenon());
+}
+
+#[test]
+fn poisoning_is_per_core() {
+    let mut small = FissionProducts::new();
+    run(&mut small, &[80.0; 4], 300.0);
+    let mut big = FissionProducts::new();
+    run(&mut big, &[80.0; 32], 300.0);
+
+    assert!((small.xenon() - big.xenon()).abs() < 1e-3);
+    assert!((small.decay_heat() - big.decay_heat()).abs() < 1e-3);
+    assert!(big.cpu_seconds > small.cpu_seconds * 7.9);
+}
+
+#[test]
+fn a_fresh_core_keeps_the_session_total() {
+    let mut products = FissionProducts::new();
+    run(&mut products, &[100.0; 2], 300.0);
+    let total = products.cpu_seconds;
+
+    products.reset();
+    assert_eq!(products.decay_heat(), 0.0);
+    assert_eq!(products.xenon(), 0.0);
+    assert_eq!(products.cpu_seconds, total);
+}
+
+#[test]
+fn a_hammered_core_stays_warm_at_idle() {
+    let load = |cpu_load: f32| ReactorInput {
+        cpu_load,
+        core_loads: vec![cpu_load; 4],
+        ..ReactorInput::default()
+    };
+    let mut rested = Reactor::with_seed(6);
+    let mut hammered = Reactor::with_seed(6);
+    for _ in 0..(300.0 / FIXED_TIMESTEP) as usize {
+        rested.step(&load(5.0));
+        hammered.step(&load(45.0));
+    }
+    // Well after the rods have dropped back in
+    for _ in 0..(10.0 / FIXED_TIMESTEP) as usize {
+        rested.step(&load(5.0));
+        hammered.step(&load(5.0));
+    }
+    assert!(hammered.core_temperature > rested.core_temperature + 5.0, "{} vs {}", hammered.core_temperature, rested.core_temperature);
+}
@@ -0,0 +1,88 @@
+// A seed and a load trace fully determine a run
+
+use nuclear_monitor::app::{App, AppConfig};
+use nuclear_monitor::metrics::ScriptedSource;
+use nuclear_monitor::reactor::{Particle, Reactor, ReactorInput};
+use nuclear_monitor::system::SystemInfo;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(33);
+
+// Idle, a climb to full load, then a bout of swapping on top
+fn trace() -> ScriptedSource {
+    let frames = (0..900)
+        .map(|frame| {
+            let load = (frame as f32 / 3.0).clamp(10.0, 100.0);
+            let swapping = if frame > 600 { 4.0e3 } else { 0.0 };
+            SystemInfo {
+                cpu_usage: load,
+                core_usage: vec![load; 4],
+                memory_usage: 60.0,
+                context_switch_rate: 40.0e3 * load / 100.0,
+                page_fault_rate: 20.0e3 * load / 100.0,
+                swap_in_rate: swapping,
+                swap_out_rate: swapping,
+                ..SystemInfo::default()
+            }
+        })
+        .collect();
+    ScriptedSource::new(frames)
+}
+
+fn seeded(seed: u64) -> App {
+    let config = AppConfig { seed: Some(seed), ..AppConfig::default() };
+    App::with_source(Box::new(trace()), config)
+}
+
+// The particles after every frame, and the frame the first meltdown started
+// in, if there was one
+fn run(app: &mut App) -> (Vec<Vec<Particle>>, Option<usize>) {
+    let mut particles = Vec::new();
+    let mut meltdown = None;
+    for frame in 0..900 {
+        app.update(FRAME);
+        particles.push(app.reactor.particles.clone());
+        if meltdown.is_none() && app.reactor.meltdowns > 0 {
+            meltdown = Some(frame);
+        }
+    }
+    (particles, meltdown)
+}
+
+#[test]
+fn same_seed_and_trace_give_the_same_run() {
+    let (mut first, mut second) = (seeded(1234), seeded(1234));
+    assert_eq!(first.reactor.seed(), 1234);
+
+    let (particles, meltdown) = run(&mut first);
+    assert!(meltdown.is_some(), "the trace should melt the core down");
+    assert!(particles.iter().any(|frame| !frame.is_empty()));
+    assert_eq!(run(&mut second), (particles, meltdown));
+
+    assert_eq!(first.reactor.total_collisions, second.reactor.total_collisions);
+    assert_eq!(first.reactor.core_temperature, second.reactor.core_temperature);
+    assert_eq!(first.reactor.history, second.reactor.history);
+}
+
+#[test]
+fn different_seeds_diverge() {
+    let (mut first, mut second) = (seeded(1), seeded(2));
+    assert_ne!(run(&mut first).0, run(&mut second).0);
+}
+
+#[test]
+fn seeded_reactors_step_identically() {
+    let input = ReactorInput {
+        cpu_load: 80.0,
+        core_loads: vec![70.0, 90.0],
+        ..ReactorInput::default()
+    };
+    let (mut first, mut second) = (Reactor::with_seed(99), Reactor::with_seed(99));
+    for _ in 0..600 {
+        first.step(&input);
+        second.step(&input);
+        assert_eq!(first.particles, second.particles);
+    }
+    assert_eq!(first.instability, second.instability);
+    assert_eq!(first.rods, second.rods);
+}
@@ -0,0 +1,50 @@
+// The fuel lattice, and the neutrons it gives off
+
+use nuclear_monitor::fuel::PELLET_RADIUS;
+use nuclear_monitor::reactor::{Particle, Reactor, ReactorInput, Species};
+
+// Light enough that a neutron can't reach a neighbouring pellet in one step
+fn light_load() -> ReactorInput {
+    ReactorInput {
+        cpu_load: 20.0,
+        core_loads: vec![20.0; 4],
+        ..ReactorInput::default()
+    }
+}
+
+fn neutrons(reactor: &Reactor) -> Vec<Particle> {
+    reactor.particles.iter().filter(|p| p.species == Species::Neutron).copied().collect()
+}
+
+#[test]
+fn spontaneous_neutrons_start_outside_their_pellet() {
+    for seed in 0..10 {
+        let mut reactor = Reactor::with_seed(seed);
+        let input = light_load();
+
+        // Until the first neutrons appear; nothing has split yet, so they are
+        // all spontaneous
+        let mut spawned = Vec::new();
+        for _ in 0..6000 {
+            reactor.step(&input);
+            spawned = neutrons(&reactor);
+            if !spawned.is_empty() {
+                break;
+            }
+        }
+        assert!(!spawned.is_empty(), "seed {}: no neutrons were emitted", seed);
+
+        for neutron in &spawned {
+            assert_eq!(reactor.fuel.pellet_at(neutron.x, neutron.y), None, "seed {}: spawned inside a pellet", seed);
+        }
+
+        // Nothing absorbs them on their first step
+        reactor.step(&input);
+        let survivors = neutrons(&reactor);
+        for (neutron, next) in spawned.iter().zip(&survivors) {
+            assert!(next.is_alive(), "seed {}: neutron absorbed on its first step", seed);
+            let moved = (next.x - neutron.x).hypot(next.y - neutron.y);
+            assert!(moved > 0.0 && moved < 3.0 * PELLET_RADIUS, "seed {}: moved {}", seed, moved);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// Operator mode: difficulty and scoring
+
+use std::fs;
+use std::path::PathBuf;
+
+use std::time::Duration;
+
+use nuclear_monitor::app::{App, AppConfig};
+use nuclear_monitor::game::{Difficulty, Game, HighScore, HighScores, OperatorControls};
+use nuclear_monitor::metrics::ScriptedSource;
+use nuclear_monitor::model::ModelKind;
+use nuclear_monitor::reactor::{Reactor, ReactorInput};
+use nuclear_monitor::system::SystemInfo;
+
+fn full_load() -> ReactorInput {
+    ReactorInput {
+        cpu_load: 100.0,
+        core_loads: vec![100.0; 4],
+        ..ReactorInput::default()
+    }
+}
+
+// Reactivity ($) at full load with every rod fully withdrawn, where the load
+// can't pull the rods out any further
+fn reactivity_at(difficulty: Difficulty) -> f32 {
+    let mut reactor = Reactor::with_seed(7);
+    reactor.set_model(ModelKind::PointKinetics);
+    reactor.rods = vec![1.0; 4];
+    reactor.rod_position = 1.0;
+    reactor.operator = Some(OperatorControls::new(1.0, difficulty.load_gain()));
+    reactor.step(&full_load());
+    reactor.reactivity
+}
+
+#[test]
+fn difficulty_scales_the_loads_own_reactivity() {
+    let easy = reactivity_at(Difficulty::Easy);
+    let normal = reactivity_at(Difficulty::Normal);
+    let hard = reactivity_at(Difficulty::Hard);
+    assert!(easy < normal && normal < hard, "easy {} normal {} hard {}", easy, normal, hard);
+}
+
+#[test]
+fn rod_keys_move_the_selected_bank() {
+    let mut controls = OperatorControls::new(0.5, 1.0);
+    controls.selected = Some(2);
+    controls.move_rods(2.0);
+    assert_eq!(controls.banks, [0.5, 0.5, 0.6, 0.5]);
+
+    // With no bank selected they all move, and stop at the ends of their travel
+    controls.selected = None;
+    controls.move_rods(-20.0);
+    assert_eq!(controls.banks, [0.0; 4]);
+
+    controls.adjust_pumps(-3.0);
+    assert!((controls.pump_demand - 0.7).abs() < 1e-6);
+    controls.adjust_pumps(5.0);
+    assert_eq!(controls.pump_demand, 1.0);
+
+    // Rods are shared out between the banks in turn
+    assert_eq!((0..6).map(|rod| OperatorControls::bank_of(rod, 32)).collect::<Vec<_>>(), [0, 1, 2, 3, 0, 1]);
+    assert_eq!(OperatorControls::bank_of(1, 2), 1);
+    assert_eq!(OperatorControls::bank_of(2, 2), 0);
+}
+
+// A steady core in the middle of the safe band
+fn calm_reactor() -> Reactor {
+    let mut reactor = Reactor::with_seed(7);
+    reactor.core_temperature = 395.0;
+    reactor.radiation_level = 50.0;
+    reactor.coolant_level = 100.0;
+    reactor.instability = 0.0;
+    reactor
+}
+
+fn scores_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("nuclears-{}-{}.json", name, std::process::id()))
+}
+
+#[test]
+fn time_in_the_band_scores_by_difficulty() {
+    let mut reactor = calm_reactor();
+    assert!(Game::in_band(&reactor), "stability {}", reactor.stability());
+    let path = scores_path("band");
+    let mut easy = Game::new(Difficulty::Easy, &path, &reactor);
+    let mut hard = Game::new(Difficulty::Hard, &path, &reactor);
+    for _ in 0..10 {
+        easy.update(&reactor, 1.0);
+        hard.update(&reactor, 1.0);
+    }
+    assert_eq!((easy.seconds_in_band, easy.seconds_played), (10.0, 10.0));
+    assert!(hard.score > easy.score * 3.9, "easy {} hard {}", easy.score, hard.score);
+
+    // Out of the band the clock runs, but the score doesn't
+    reactor.core_temperature = 1000.0;
+    reactor.radiation_level = 100.0;
+    assert!(!Game::in_band(&reactor));
+    let score = easy.score;
+    easy.update(&reactor, 5.0);
+    assert_eq!((easy.score, easy.seconds_played), (score, 15.0));
+}
+
+#[test]
+fn a_meltdown_ends_the_game_and_saves_the_score() {
+    let path = scores_path("meltdown");
+    let _ = fs::remove_file(&path);
+    let mut reactor = calm_reactor();
+    let mut game = Game::new(Difficulty::Normal, &path, &reactor);
+    for _ in 0..30 {
+        game.update(&reactor, 1.0);
+    }
+
+    reactor.meltdowns += 1;
+    game.update(&reactor, 1.0);
+    let result = game.last_result.expect("the game should have ended");
+    assert_eq!((result.score, result.rank), (300, Some(0)));
+    assert_eq!((game.score, game.seconds_played), (0.0, 0.0));
+    assert_eq!(game.notice, None);
+
+    // The table was written, and the next game plays against it
+    let saved = HighScores::load(&path).unwrap();
+    assert_eq!(saved.best().map(|best| (best.score, best.difficulty)), Some((300, Difficulty::Normal)));
+    game.update(&reactor, 1.0);
+    assert_eq!(game.seconds_played, 1.0);
+    let _ = fs::remove_file(&path);
+}
+
+fn entry(score: u32) -> HighScore {
+    HighScore { score, difficulty: Difficulty::Normal, seconds_in_band: 0.0, achieved: 0 }
+}
+
+#[test]
+fn the_table_keeps_the_best_ten() {
+    let mut table = HighScores::in_memory("unused.json");
+    for score in 1..=10 {
+        table.record(entry(score * 10));
+    }
+    assert_eq!(table.best().map(|best| best.score), Some(100));
+
+    // A tie ranks below the score it ties with, and the lowest drops off
+    assert_eq!(table.record(entry(50)), Some(6));
+    assert_eq!(table.entries.len(), 10);
+    assert_eq!(table.entries.last().map(|last| last.score), Some(20));
+    assert_eq!(table.record(entry(5)), None);
+    assert_eq!(table.record(entry(500)), Some(0));
+
+    // A table that stands in for an unreadable file is never saved over it
+    assert!(table.save().is_err());
+}
+
+#[test]
+fn unreadable_scores_are_not_overwritten() {
+    let path = scores_path("corrupt");
+    fs::write(&path, "not json").unwrap();
+    assert!(HighScores::load(&path).is_err());
+
+    let mut reactor = calm_reactor();
+    let mut game = Game::new(Difficulty::Normal, &path, &reactor);
+    assert!(game.notice.is_some());
+    game.update(&reactor, 10.0);
+    reactor.meltdowns += 1;
+    game.update(&reactor, 1.0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "not json");
+    let _ = fs::remove_file(&path);
+
+    // A missing file is just an empty table
+    assert!(HighScores::load(scores_path("missing")).unwrap().entries.is_empty());
+}
+
+#[test]
+fn a_stalled_frame_scores_only_the_simulated_time() {
+    let path = scores_path("stall");
+    let frames = vec![SystemInfo { cpu_usage: 30.0, core_usage: vec![30.0; 4], ..SystemInfo::default() }];
+    let config = AppConfig {
+        seed: Some(1),
+        game: Some(Difficulty::Normal),
+        high_score_path: Some(path.clone()),
+        ..AppConfig::default()
+    };
+    let mut app = App::with_source(Box::new(ScriptedSource::new(frames)), config);
+
+    // A suspended terminal comes back ten seconds later
+    app.update(Duration::from_secs(10));
+    let game = app.game.as_ref().unwrap();
+    assert!(game.seconds_played > 0.0 && game.seconds_played <= 0.25 + 1e-3, "{} s played", game.seconds_played);
+    assert!(game.seconds_in_band <= game.seconds_played);
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,78 @@
+// The collision broad phase: the grid must offer every pair that can touch,
+// each of them once
+
+use std::collections::HashSet;
+
+use nuclear_monitor::grid::SpatialGrid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const REACH: f32 = 0.03;
+
+fn touching(positions: &[(f32, f32)]) -> HashSet<(usize, usize)> {
+    let mut pairs = HashSet::new();
+    for i in 0..positions.len() {
+        for j in i + 1..positions.len() {
+            let (a, b) = (positions[i], positions[j]);
+            if (a.0 - b.0).hypot(a.1 - b.1) < REACH {
+                pairs.insert((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+fn candidates(grid: &mut SpatialGrid, positions: &[(f32, f32)]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    grid.rebuild(positions.iter().copied());
+    grid.candidate_pairs(&mut pairs);
+    pairs
+}
+
+#[test]
+fn every_touching_pair_is_a_candidate_once() {
+    let mut rng = StdRng::seed_from_u64(9);
+    let mut grid = SpatialGrid::new(REACH);
+    for count in [0, 1, 50, 2000] {
+        let positions: Vec<_> = (0..count).map(|_| (rng.random::<f32>(), rng.random::<f32>())).collect();
+        let pairs = candidates(&mut grid, &positions);
+
+        let unique: HashSet<_> = pairs.iter().copied().collect();
+        assert_eq!(unique.len(), pairs.len(), "a pair was offered twice");
+        assert!(pairs.iter().all(|&(i, j)| i < j && j < count));
+        let missing: Vec<_> = touching(&positions).difference(&unique).copied().collect();
+        assert!(missing.is_empty(), "{} particles: missed {:?}", count, missing);
+        // And it is far fewer than checking everything against everything
+        if count == 2000 {
+            assert!(pairs.len() < count * (count - 1) / 2 / 20, "{} candidates", pairs.len());
+        }
+    }
+}
+
+#[test]
+fn pairs_across_cell_edges_and_outside_the_square_are_found() {
+    let mut grid = SpatialGrid::new(REACH);
+    // Either side of a cell boundary, diagonally, and on or past the edges
+    let positions = [
+        (0.5 - 0.001, 0.5 - 0.001),
+        (0.5 + 0.001, 0.5 + 0.001),
+        (-0.01, 0.2),
+        (0.005, 0.2),
+        (1.0, 1.0),
+        (0.99, 1.01),
+    ];
+    let pairs: HashSet<_> = candidates(&mut grid, &positions).into_iter().collect();
+    for pair in [(0, 1), (2, 3), (4, 5)] {
+        assert!(pairs.contains(&pair), "missed {:?}", pair);
+    }
+}
+
+#[test]
+fn the_same_positions_give_the_same_order() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let positions: Vec<_> = (0..500).map(|_| (rng.random::<f32>(), rng.random::<f32>())).collect();
+    let first = candidates(&mut SpatialGrid::new(REACH), &positions);
+    let mut reused = SpatialGrid::new(REACH);
+    candidates(&mut reused, &positions[..100]);
+    assert_eq!(candidates(&mut reused, &positions), first);
+}
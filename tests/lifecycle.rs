@@ -0,0 +1,111 @@
+// The reactor lifecycle: SCRAM, meltdown, cooldown and restart, with the
+// session's history kept through all of them
+
+use nuclear_monitor::reactor::{Reactor, ReactorInput, ReactorPhase, FIXED_TIMESTEP, HISTORY_SIZE};
+
+fn load(cpu_load: f32) -> ReactorInput {
+    ReactorInput {
+        cpu_load,
+        core_loads: vec![cpu_load; 4],
+        ..ReactorInput::default()
+    }
+}
+
+// The OOM killer striking melts the core down on the spot
+fn oom_kills(kills: u64) -> ReactorInput {
+    ReactorInput { oom_kills: Some(kills), ..load(50.0) }
+}
+
+fn run(reactor: &mut Reactor, input: &ReactorInput, seconds: f32) {
+    for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+        reactor.step(input);
+    }
+}
+
+// Seconds until the reactor is back in operation, if it gets there within a minute
+fn time_to_operating(reactor: &mut Reactor, input: &ReactorInput) -> Option<f32> {
+    (1..=3600).find_map(|step| {
+        reactor.step(input);
+        (reactor.phase == ReactorPhase::Operating).then_some(step as f32 * FIXED_TIMESTEP)
+    })
+}
+
+#[test]
+fn scram_drops_the_rods_and_cools_the_core_before_resuming() {
+    let mut reactor = Reactor::with_seed(5);
+    let busy = load(40.0);
+    run(&mut reactor, &busy, 10.0);
+    let (rods, temperature) = (reactor.rod_position, reactor.core_temperature);
+    assert_eq!(reactor.phase, ReactorPhase::Operating);
+    assert!(rods > 0.35, "rods at {}", rods);
+
+    reactor.scram();
+    assert_eq!((reactor.phase, reactor.scrams), (ReactorPhase::Cooldown, 1));
+    // Only an operating core can be scrammed
+    reactor.scram();
+    assert_eq!(reactor.scrams, 1);
+
+    // The rods drop in and nothing new is emitted, however busy the machine is
+    let particles = reactor.particles.len();
+    run(&mut reactor, &busy, 1.0);
+    assert!(reactor.rod_position < rods * 0.1, "rods only down to {}", reactor.rod_position);
+    assert!(reactor.core_temperature < temperature);
+    assert!(reactor.particles.len() <= particles);
+
+    let cooled = time_to_operating(&mut reactor, &busy).expect("the core should come back");
+    assert!(cooled + 1.0 >= 3.0, "back after only {} s", cooled + 1.0);
+    assert!(reactor.core_temperature < 300.0);
+}
+
+#[test]
+fn a_meltdown_clears_by_itself_and_keeps_the_history() {
+    let mut reactor = Reactor::with_seed(5);
+    run(&mut reactor, &oom_kills(0), 5.0);
+    let collisions = reactor.total_collisions;
+
+    reactor.step(&oom_kills(1));
+    assert_eq!(reactor.phase, ReactorPhase::Meltdown);
+    assert!(reactor.is_exploding());
+    assert_eq!(reactor.meltdowns, 1);
+
+    // The explosion plays out, then what's left of the core is replaced and cooled
+    run(&mut reactor, &oom_kills(1), 5.5);
+    assert_eq!(reactor.phase, ReactorPhase::Cooldown);
+    assert!(!reactor.is_exploding());
+    assert!(time_to_operating(&mut reactor, &oom_kills(1)).is_some());
+
+    assert_eq!(reactor.meltdowns, 1);
+    // The chart kept going all the way through
+    assert_eq!(reactor.history.len(), HISTORY_SIZE);
+    assert!(reactor.total_collisions >= collisions);
+}
+
+#[test]
+fn restart_puts_a_fresh_core_into_operation_at_once() {
+    let mut reactor = Reactor::with_seed(5);
+    run(&mut reactor, &oom_kills(0), 2.0);
+    reactor.step(&oom_kills(1));
+    run(&mut reactor, &oom_kills(1), 1.0);
+    let history = reactor.history.clone();
+
+    reactor.restart();
+    assert_eq!(reactor.phase, ReactorPhase::Operating);
+    assert!(reactor.particles.is_empty());
+    assert_eq!(reactor.core_temperature, 220.0);
+    assert_eq!(reactor.collision_rate(), 0.0);
+    // Session statistics and the chart carry on
+    assert_eq!(reactor.meltdowns, 1);
+    assert_eq!(reactor.history, history);
+}
+
+#[test]
+fn no_refuelling_while_the_core_blows_up() {
+    let mut reactor = Reactor::with_seed(5);
+    reactor.step(&oom_kills(0));
+    reactor.refuel();
+    assert_eq!(reactor.refuels, 1);
+
+    reactor.step(&oom_kills(1));
+    reactor.refuel();
+    assert_eq!(reactor.refuels, 1);
+}
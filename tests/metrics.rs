@@ -0,0 +1,94 @@
+// Metrics sources: the synthetic generator, scripts, and the trait itself
+
+use nuclear_monitor::app::{App, AppConfig};
+use nuclear_monitor::metrics::{MetricsSource, ScriptedSource, SyntheticSource};
+use nuclear_monitor::system::SystemInfo;
+use std::time::Duration;
+
+fn run(source: &mut impl MetricsSource, step: Duration, steps: u32) -> SystemInfo {
+    for _ in 0..steps {
+        source.advance(step);
+        source.sample();
+    }
+    source.sample().expect("synthetic sources always have a snapshot")
+}
+
+#[test]
+fn synthetic_load_follows_simulated_time_not_samples() {
+    // The same second at 40 and at 20 frames a second
+    let fast = run(&mut SyntheticSource::new(4), Duration::from_millis(25), 40);
+    let slow = run(&mut SyntheticSource::new(4), Duration::from_millis(50), 20);
+    assert_eq!(fast, slow);
+
+    // Sampling again without time passing changes nothing
+    let mut source = SyntheticSource::new(4);
+    source.advance(Duration::from_secs(3));
+    let first = source.sample();
+    assert_eq!(source.sample(), first);
+    assert_eq!(first.map(|info| info.uptime), Some(3));
+}
+
+#[test]
+fn synthetic_load_repeats_every_period() {
+    let period = Duration::from_secs(10);
+    let mut source = SyntheticSource::new(2).with_period(period);
+    let start = source.sample().unwrap();
+    source.advance(period / 4);
+    let quarter = source.sample().unwrap();
+    source.advance(period * 3 / 4);
+    let full = source.sample().unwrap();
+
+    assert_ne!(quarter.cpu_usage, start.cpu_usage);
+    assert_eq!(full.core_usage, start.core_usage);
+    for info in [&start, &quarter, &full] {
+        assert_eq!(info.core_usage.len(), 2);
+        assert!(info.core_usage.iter().all(|load| (20.0..=95.0).contains(load)), "{:?}", info.core_usage);
+    }
+}
+
+#[test]
+fn scripted_source_plays_one_frame_per_sample_then_holds() {
+    let mut source = ScriptedSource::from_loads(&[10.0, 50.0, 90.0]);
+    // Time passing doesn't skip frames
+    source.advance(Duration::from_secs(60));
+    let loads: Vec<f32> = (0..5).map(|_| source.sample().unwrap().cpu_usage).collect();
+    assert_eq!(loads, [10.0, 50.0, 90.0, 90.0, 90.0]);
+    assert!(source.is_finished());
+
+    assert_eq!(ScriptedSource::new(Vec::new()).sample(), None);
+}
+
+#[test]
+fn looping_script_starts_over() {
+    let mut source = ScriptedSource::from_loads(&[10.0, 50.0]).looping();
+    let loads: Vec<f32> = (0..5).map(|_| source.sample().unwrap().cpu_usage).collect();
+    assert_eq!(loads, [10.0, 50.0, 10.0, 50.0, 10.0]);
+    assert!(!source.is_finished());
+}
+
+// A source of your own only has to produce snapshots
+struct Constant(f32);
+
+impl MetricsSource for Constant {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        Some(SystemInfo {
+            cpu_usage: self.0,
+            core_usage: vec![self.0; 2],
+            ..SystemInfo::default()
+        })
+    }
+}
+
+#[test]
+fn any_source_drives_the_app() {
+    let source: Box<dyn MetricsSource> = Box::new(Constant(42.0));
+    assert_eq!(source.status(), None);
+    assert!(source.playback().is_none());
+
+    let config = AppConfig { seed: Some(1), ..AppConfig::default() };
+    let mut app = App::with_source(source, config);
+    app.update(Duration::from_millis(100));
+    assert_eq!(app.system_info.cpu_usage, 42.0);
+    assert_eq!(app.reactor.rods.len(), 2);
+    assert_eq!(app.source_status(), None);
+}
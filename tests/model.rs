@@ -0,0 +1,124 @@
+// Reactor models: the arcade maps and point kinetics with delayed neutrons
+
+use nuclear_monitor::model::{ArcadeModel, ModelInput, ModelKind, ModelOutput, PointKineticsModel, ReactorModel};
+use nuclear_monitor::reactor::{Reactor, ReactorInput, FIXED_TIMESTEP};
+
+fn input(rod_position: f32, cpu_load: f32) -> ModelInput {
+    ModelInput {
+        rod_position,
+        cpu_load,
+        core_temperature: 220.0,
+        fuel: 1.0,
+        ..ModelInput::default()
+    }
+}
+
+// Step for `seconds`, carrying the core temperature over between steps
+fn run(model: &mut dyn ReactorModel, input: &mut ModelInput, seconds: f32) -> ModelOutput {
+    let mut output = ModelOutput::default();
+    for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+        output = model.step(input, FIXED_TIMESTEP);
+        input.core_temperature = output.core_temperature;
+    }
+    output
+}
+
+#[test]
+fn inserted_rods_keep_the_core_at_source_level() {
+    let mut model = PointKineticsModel::new();
+    let output = run(&mut model, &mut input(0.0, 0.0), 30.0);
+    assert!(output.reactivity < 0.0);
+    assert!(output.power < 0.1, "subcritical core at power {}", output.power);
+    assert!(output.core_temperature < 300.0);
+}
+
+#[test]
+fn delayed_neutrons_make_a_small_insertion_controllable() {
+    let mut model = PointKineticsModel::new();
+    // About 30 cents above critical
+    let mut state = input(0.6, 0.0);
+    let first = model.step(&state, FIXED_TIMESTEP);
+    assert!(first.reactivity > 0.2 && first.reactivity < 0.5, "${}", first.reactivity);
+
+    // A prompt jump to well under double the source level it started at, then
+    // a period of seconds, not microseconds
+    let jump = run(&mut model, &mut state, 0.2).power;
+    assert!(jump < 2.0 * 0.05, "prompt jump to {}", jump);
+    assert!(model.period() > 1.0 && model.period().is_finite(), "period {}", model.period());
+
+    // And it does keep rising
+    let later = run(&mut model, &mut state, 10.0).power;
+    assert!(later > jump);
+}
+
+#[test]
+fn temperature_feedback_settles_a_withdrawn_core() {
+    let mut model = PointKineticsModel::new();
+    let mut state = input(1.0, 0.0);
+    run(&mut model, &mut state, 120.0);
+    let settled = run(&mut model, &mut state, 10.0);
+
+    // Hot enough for the feedback to have cancelled most of the rods' worth
+    assert!(settled.reactivity.abs() < 0.1, "${} after two minutes", settled.reactivity);
+    assert!(settled.power > 0.1 && settled.power < 5.0, "power {}", settled.power);
+    assert!(settled.core_temperature > 300.0);
+}
+
+#[test]
+fn load_inserts_reactivity_and_poisons_take_it_away() {
+    let reactivity = |state: ModelInput| PointKineticsModel::new().step(&state, FIXED_TIMESTEP).reactivity;
+    let clean = reactivity(input(0.5, 0.0));
+
+    let loaded = reactivity(input(0.5, 1.0));
+    assert!((loaded - clean - 0.15).abs() < 1e-3, "load worth ${}", loaded - clean);
+
+    let poisoned = reactivity(ModelInput { xenon: 0.5, ..input(0.5, 0.0) });
+    assert!(poisoned < clean - 0.4);
+    let burnt = reactivity(ModelInput { fuel: 0.5, ..input(0.5, 0.0) });
+    assert!(burnt < clean - 0.4);
+}
+
+#[test]
+fn reset_starts_a_fresh_core() {
+    let mut model = PointKineticsModel::new();
+    let fresh = model.step(&input(0.6, 0.0), FIXED_TIMESTEP);
+    run(&mut model, &mut input(1.0, 1.0), 30.0);
+    model.reset();
+    assert_eq!(model.step(&input(0.6, 0.0), FIXED_TIMESTEP), fresh);
+}
+
+#[test]
+fn arcade_temperature_follows_the_rods() {
+    let mut model = ArcadeModel;
+    let low = run(&mut model, &mut input(0.2, 0.0), 20.0);
+    let high = run(&mut model, &mut input(0.8, 0.0), 20.0);
+    assert!((low.core_temperature - (220.0 + 700.0 * 0.2)).abs() < 5.0, "{}", low.core_temperature);
+    assert!((high.core_temperature - (220.0 + 700.0 * 0.8)).abs() < 5.0, "{}", high.core_temperature);
+    assert_eq!(high.reactivity, 0.0);
+}
+
+#[test]
+fn switching_models_keeps_the_core_temperature() {
+    let mut reactor = Reactor::with_seed(8);
+    let input = ReactorInput { cpu_load: 45.0, core_loads: vec![45.0; 2], ..ReactorInput::default() };
+    for _ in 0..600 {
+        reactor.step(&input);
+    }
+    let temperature = reactor.core_temperature;
+
+    reactor.set_model(ModelKind::PointKinetics);
+    assert_eq!(reactor.model_kind(), ModelKind::PointKinetics);
+    assert_eq!(reactor.core_temperature, temperature);
+    assert!(!reactor.model_details().is_empty());
+    reactor.set_model(ModelKind::PointKinetics.next());
+    assert_eq!(reactor.model_kind(), ModelKind::Arcade);
+}
+
+#[test]
+fn a_recorded_model_name_is_accepted_on_the_command_line() {
+    for kind in [ModelKind::Arcade, ModelKind::PointKinetics] {
+        let recorded = serde_json::to_string(&kind).unwrap();
+        assert_eq!(recorded.trim_matches('"').parse::<ModelKind>(), Ok(kind));
+        assert_eq!(serde_json::from_str::<ModelKind>(&recorded).unwrap(), kind);
+    }
+}
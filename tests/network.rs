@@ -0,0 +1,64 @@
+// Which interfaces count towards network throughput
+
+use nuclear_monitor::network::InterfaceFilter;
+
+fn filter(include: &[&str], exclude: &[&str]) -> InterfaceFilter {
+    InterfaceFilter {
+        include: include.iter().map(|p| p.to_string()).collect(),
+        exclude: exclude.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+#[test]
+fn default_filter_leaves_out_loopback_and_veth() {
+    let default = InterfaceFilter::default();
+    assert!(default.matches("eth0"));
+    assert!(default.matches("wlp3s0"));
+    assert!(!default.matches("lo"));
+    assert!(!default.matches("veth1a2b3c"));
+    // Only the whole name counts
+    assert!(default.matches("lo0"));
+    assert!(default.matches("myveth0"));
+}
+
+#[test]
+fn exact_names_match_only_themselves() {
+    let eth0 = filter(&["eth0"], &[]);
+    assert!(eth0.matches("eth0"));
+    assert!(!eth0.matches("eth01"));
+    assert!(!eth0.matches("eth"));
+    assert!(!eth0.matches("ETH0"));
+}
+
+#[test]
+fn star_matches_any_run_of_characters() {
+    let wired = filter(&["en*", "eth*"], &[]);
+    assert!(wired.matches("enp0s31f6"));
+    assert!(wired.matches("eth"));
+    assert!(!wired.matches("wlan0"));
+
+    // Backtracks when the rest of the pattern doesn't fit the first time
+    let tagged = filter(&["*.1*0"], &[]);
+    assert!(tagged.matches("eth0.100"));
+    assert!(tagged.matches("bond.1.10"));
+    assert!(!tagged.matches("eth0.101"));
+
+    assert!(filter(&["*"], &[]).matches(""));
+}
+
+#[test]
+fn question_mark_matches_exactly_one_character() {
+    let eth = filter(&["eth?"], &[]);
+    assert!(eth.matches("eth0"));
+    assert!(eth.matches("eth:"));
+    assert!(!eth.matches("eth"));
+    assert!(!eth.matches("eth10"));
+}
+
+#[test]
+fn exclusions_win_over_inclusions() {
+    let filter = filter(&["eth*"], &["eth0:*"]);
+    assert!(filter.matches("eth0"));
+    assert!(!filter.matches("eth0:1"));
+    assert!(!filter.matches("docker0"));
+}
@@ -0,0 +1,146 @@
+// /proc parsing, checked against fixture files under tests/fixtures/proc
+
+use nuclear_monitor::disk::{total_rates, DiskStats};
+use nuclear_monitor::procfs::{DiskCounters, InterfaceCounters, KernelCounters, ProcFs};
+use nuclear_monitor::psi::PressureAverages;
+
+fn fixtures() -> ProcFs {
+    ProcFs::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc"))
+}
+
+// An older kernel: CPU pressure without a "full" line, no I/O pressure at all
+fn legacy_fixtures() -> ProcFs {
+    ProcFs::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc-legacy"))
+}
+
+#[test]
+fn counters_read_stat_and_vmstat() {
+    assert_eq!(fixtures().counters(), Some(KernelCounters {
+        context_switches: 91422108,
+        interrupts: 48812231,
+        page_faults: 310554120,
+        major_faults: 18822,
+        swap_ins: 1204,
+        swap_outs: 5530,
+    }));
+}
+
+#[test]
+fn counter_rates_between_readings() {
+    let earlier = KernelCounters { swap_ins: 1000, swap_outs: 4000, ..KernelCounters::default() };
+    let now = KernelCounters { swap_ins: 1500, swap_outs: 3000, major_faults: 40, ..KernelCounters::default() };
+    let rates = now.rates_since(&earlier, 0.5);
+
+    assert_eq!(rates.swap_ins, 1000.0);
+    assert_eq!(rates.major_faults, 80.0);
+    // A counter that went backwards reads as no activity
+    assert_eq!(rates.swap_outs, 0.0);
+}
+
+#[test]
+fn diskstats_lists_whole_devices_that_did_io() {
+    let disks = fixtures().diskstats().expect("fixture diskstats");
+    let names: Vec<&str> = disks.iter().map(|(name, _)| name.as_str()).collect();
+
+    // Idle loop devices and partitions are left out, but loop10 isn't a partition of loop1
+    assert_eq!(names, ["loop10", "sda", "nvme0n1", "dm-0", "zram0", "ram0"]);
+
+    let (_, sda) = &disks[1];
+    assert_eq!(*sda, DiskCounters {
+        reads: 184220,
+        read_bytes: 9466340 * 512,
+        writes: 402116,
+        written_bytes: 28839360 * 512,
+        busy_ms: 196480,
+    });
+}
+
+#[test]
+fn diskstats_missing_without_procfs() {
+    assert_eq!(ProcFs::new("/nonexistent").diskstats(), None);
+}
+
+#[test]
+fn disk_rates_between_readings() {
+    let earlier = DiskCounters { reads: 100, read_bytes: 1 << 20, writes: 50, written_bytes: 0, busy_ms: 1000 };
+    let now = DiskCounters { reads: 300, read_bytes: 3 << 20, writes: 150, written_bytes: 1 << 20, busy_ms: 1500 };
+    let stats = DiskStats::new("sda", &now, Some(&earlier), 2.0);
+
+    assert_eq!(stats.read_rate, (1 << 20) as f32);
+    assert_eq!(stats.write_rate, (1 << 19) as f32);
+    assert_eq!(stats.iops(), 150.0);
+    assert_eq!(stats.utilization, 0.25);
+
+    // A first reading has no rates yet
+    assert_eq!(DiskStats::new("sda", &now, None, 2.0).iops(), 0.0);
+}
+
+#[test]
+fn disk_totals_count_every_byte_once() {
+    // Everything since boot, over one second
+    let disks: Vec<DiskStats> = fixtures()
+        .diskstats()
+        .expect("fixture diskstats")
+        .iter()
+        .map(|(name, counters)| DiskStats::new(name, counters, Some(&DiskCounters::default()), 1.0))
+        .collect();
+
+    // Partitions are already left out; dm-0 and the file behind loop10 sit on
+    // the disks, and zram0 and ram0 are memory
+    let bytes = |sectors: &[u64]| sectors.iter().map(|sectors| (sectors * 512) as f32).sum::<f32>();
+    let (read, written) = total_rates(&disks);
+    assert_eq!(read, bytes(&[9466340, 71822814]));
+    assert_eq!(written, bytes(&[28839360, 145098332]));
+}
+
+#[test]
+fn net_dev_reads_every_interface() {
+    let interfaces = fixtures().net_dev().expect("fixture net/dev");
+    let names: Vec<&str> = interfaces.iter().map(|(name, _)| name.as_str()).collect();
+    // The truncated line is skipped, not the whole file
+    assert_eq!(names, ["lo", "eth0", "eth0:1", "veth1a2b3c"]);
+
+    // A counter too wide for its column runs straight on from the colon
+    assert_eq!(interfaces[1].1, InterfaceCounters {
+        rx_bytes: 4294967296123,
+        rx_packets: 3520117,
+        rx_errors: 3,
+        rx_drops: 12,
+        tx_bytes: 196483302,
+        tx_packets: 1209842,
+        tx_errors: 1,
+        tx_drops: 0,
+    });
+    // An alias keeps the colon in its name
+    assert_eq!(interfaces[2].1.rx_bytes, 2048);
+    assert_eq!(interfaces[2].1.tx_packets, 8);
+}
+
+#[test]
+fn pressure_reads_some_and_full_for_every_resource() {
+    let pressure = fixtures().pressure();
+    let io = pressure.io.expect("fixture io pressure");
+    assert_eq!(io.some, PressureAverages { avg10: 20.0, avg60: 11.0, avg300: 6.5 });
+    assert_eq!(io.full, Some(PressureAverages { avg10: 8.0, avg60: 4.0, avg300: 2.0 }));
+    assert_eq!(io.stall(), 20.0);
+
+    // Full stalls are part of the some figure, so they don't add to it
+    assert_eq!(pressure.memory.map(|memory| memory.stall()), Some(4.0));
+    assert_eq!(pressure.stall(), Some(20.0));
+}
+
+#[test]
+fn pressure_degrades_on_older_kernels() {
+    let pressure = legacy_fixtures().pressure();
+    let cpu = pressure.cpu.expect("fixture cpu pressure");
+    assert_eq!(cpu.full, None);
+    assert_eq!(cpu.stall(), 7.0);
+
+    // A malformed file counts as missing, like an absent one
+    assert_eq!(pressure.memory, None);
+    assert_eq!(pressure.io, None);
+    assert_eq!(pressure.stall(), Some(7.0));
+
+    // Without PSI there's no stall, and the reactor falls back on temperature
+    assert_eq!(ProcFs::new("/nonexistent").pressure().stall(), None);
+}
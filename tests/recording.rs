@@ -0,0 +1,147 @@
+// Recording a session and replaying it reproduces the run step for step,
+// operator actions and pauses included
+
+use crossterm::event::{KeyCode, KeyEvent};
+use nuclear_monitor::app::{App, AppConfig};
+use nuclear_monitor::metrics::{Playback, ScriptedSource};
+use nuclear_monitor::reactor::{Particle, FIXED_TIMESTEP};
+use nuclear_monitor::recording::ReplaySource;
+use nuclear_monitor::system::SystemInfo;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// The recorded session runs one step per update, so every step can be checked
+fn one_step() -> Duration {
+    Duration::from_secs_f32(FIXED_TIMESTEP)
+}
+
+// Idle, a climb to full load, then a bout of swapping on top
+fn trace() -> ScriptedSource {
+    let frames = (0..1800)
+        .map(|step| {
+            let load = (step as f32 / 6.0).clamp(10.0, 100.0);
+            let swapping = if step > 1200 { 4.0e3 } else { 0.0 };
+            SystemInfo {
+                cpu_usage: load,
+                core_usage: vec![load; 4],
+                memory_usage: 60.0,
+                context_switch_rate: 40.0e3 * load / 100.0,
+                page_fault_rate: 20.0e3 * load / 100.0,
+                swap_in_rate: swapping,
+                swap_out_rate: swapping,
+                ..SystemInfo::default()
+            }
+        })
+        .collect();
+    ScriptedSource::new(frames)
+}
+
+// Keys pressed during the recorded session, before the given step
+const KEYS: &[(u64, KeyCode)] = &[
+    (100, KeyCode::Char('m')),
+    (300, KeyCode::Char('x')),
+    (420, KeyCode::Char('r')),
+    (450, KeyCode::Char('f')),
+    (500, KeyCode::Char('g')),
+    (501, KeyCode::Up),
+    (501, KeyCode::Up),
+    (620, KeyCode::Char('1')),
+    (621, KeyCode::Down),
+    (700, KeyCode::Char('m')),
+];
+
+// The app is paused for a while before this step
+const PAUSED_AT: u64 = 250;
+
+struct Run {
+    // The particles after every step
+    particles: Vec<Vec<Particle>>,
+    // The step the first meltdown happened in
+    meltdown: Option<u64>,
+}
+
+fn record(path: &Path, steps: u64) -> Run {
+    let config = AppConfig { seed: Some(42), ..AppConfig::default() };
+    let mut app = App::with_recording(Box::new(trace()), config, path).unwrap();
+
+    let mut run = Run { particles: Vec::new(), meltdown: None };
+    while app.steps() < steps {
+        let step = app.steps();
+        for (_, key) in KEYS.iter().filter(|(at, _)| *at == step) {
+            app.handle_key(KeyEvent::from(*key));
+        }
+        if step == PAUSED_AT {
+            app.handle_key(KeyEvent::from(KeyCode::Char('p')));
+            for _ in 0..30 {
+                app.update(one_step());
+            }
+            assert_eq!(app.steps(), step, "steps ran while paused");
+            app.handle_key(KeyEvent::from(KeyCode::Char('p')));
+        }
+
+        app.update(one_step());
+        assert_eq!(app.steps(), step + 1, "one update should run one step");
+        run.particles.push(app.reactor.particles.clone());
+        if run.meltdown.is_none() && app.reactor.meltdowns > 0 {
+            run.meltdown = Some(step);
+        }
+    }
+    run
+}
+
+fn replay(path: &Path) -> App {
+    let replay = ReplaySource::open(path).unwrap();
+    let config = replay.config().cloned().expect("the recording has a header");
+    assert_eq!(config.seed, Some(42));
+    App::with_source(Box::new(replay), config)
+}
+
+fn recording_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("nuclears-{}-{}.jsonl", name, std::process::id()))
+}
+
+#[test]
+fn replay_reproduces_the_meltdown_step() {
+    let path = recording_path("meltdown");
+    let recorded = record(&path, 2400);
+    let meltdown = recorded.meltdown.expect("the trace should melt the core down");
+    assert!(meltdown > KEYS.last().unwrap().0, "meltdown at step {} comes before the last key", meltdown);
+
+    let mut app = replay(&path);
+    let mut meltdown_step = None;
+    for step in 0..recorded.particles.len() as u64 {
+        app.update(one_step());
+        assert_eq!(app.reactor.particles, recorded.particles[step as usize], "diverged at step {}", step);
+        if meltdown_step.is_none() && app.reactor.meltdowns > 0 {
+            meltdown_step = Some(step);
+        }
+    }
+    assert_eq!(meltdown_step, Some(meltdown));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn replay_matches_at_any_frame_rate_and_speed() {
+    let path = recording_path("speed");
+    let recorded = record(&path, 1200);
+
+    let mut app = replay(&path);
+    let mut frame = 0;
+    while app.steps() < recorded.particles.len() as u64 {
+        // Keys that would change the simulation do nothing in a replay
+        if frame == 10 {
+            app.handle_key(KeyEvent::from(KeyCode::Char('x')));
+        }
+        if frame == 20 {
+            app.handle_key(KeyEvent::from(KeyCode::Char('+')));
+            assert_eq!(app.playback().map(Playback::speed), Some(2.0));
+        }
+        app.update(Duration::from_millis(33));
+        let step = app.steps() as usize;
+        if step <= recorded.particles.len() {
+            assert_eq!(app.reactor.particles, recorded.particles[step - 1], "diverged at step {}", step);
+        }
+        frame += 1;
+    }
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,77 @@
+// Control rods: one per core, each following its own core's load
+
+use nuclear_monitor::reactor::{Reactor, ReactorInput};
+
+fn cores(loads: Vec<f32>) -> ReactorInput {
+    ReactorInput {
+        cpu_load: loads.iter().sum::<f32>() / loads.len() as f32,
+        core_loads: loads,
+        ..ReactorInput::default()
+    }
+}
+
+fn run(reactor: &mut Reactor, input: &ReactorInput, seconds: f32) {
+    for _ in 0..(seconds * 60.0) as usize {
+        reactor.step(input);
+    }
+}
+
+#[test]
+fn one_pegged_core_withdraws_one_rod() {
+    let mut loads = vec![5.0; 32];
+    loads[7] = 100.0;
+    let mut reactor = Reactor::with_seed(3);
+    run(&mut reactor, &cores(loads), 10.0);
+
+    assert_eq!(reactor.rods.len(), 32);
+    for (core, rod) in reactor.rods.iter().enumerate() {
+        if core == 7 {
+            assert!(*rod > 0.95, "pegged core's rod at {}", rod);
+        } else {
+            assert!(*rod < 0.1, "idle core {}'s rod at {}", core, rod);
+        }
+    }
+    // The shared position is only the average, which hides the pegged core
+    let average = reactor.rods.iter().sum::<f32>() / 32.0;
+    assert!((reactor.rod_position - average).abs() < 1e-6);
+    assert!(reactor.rod_position < 0.15);
+}
+
+#[test]
+fn rods_move_gradually() {
+    let mut reactor = Reactor::with_seed(3);
+    let input = cores(vec![100.0, 0.0]);
+    reactor.step(&input);
+    let mut previous = reactor.rods[0];
+    assert!(previous < 0.5, "a rod jumped to {} in one step", previous);
+
+    // And keep closing in on their targets without overshooting
+    for _ in 0..300 {
+        reactor.step(&input);
+        assert!(reactor.rods[0] >= previous && reactor.rods[0] <= 1.0);
+        assert!(reactor.rods[1] >= 0.0);
+        previous = reactor.rods[0];
+    }
+    assert!(reactor.rods[0] > 0.9 && reactor.rods[1] < 0.1, "{:?}", reactor.rods);
+}
+
+#[test]
+fn new_cores_start_at_the_average_rod() {
+    let mut reactor = Reactor::with_seed(3);
+    run(&mut reactor, &cores(vec![80.0, 20.0]), 10.0);
+    let average = reactor.rod_position;
+
+    // A hotplugged core gets a rod where the others are on average
+    reactor.step(&cores(vec![80.0, 20.0, 50.0, 50.0]));
+    assert_eq!(reactor.rods.len(), 4);
+    assert!((reactor.rods[2] - average).abs() < 0.02, "{} vs {}", reactor.rods[2], average);
+}
+
+#[test]
+fn without_per_core_loads_one_rod_follows_the_total() {
+    let mut reactor = Reactor::with_seed(3);
+    let input = ReactorInput { cpu_load: 70.0, ..ReactorInput::default() };
+    run(&mut reactor, &input, 10.0);
+    assert_eq!(reactor.rods.len(), 1);
+    assert!((reactor.rods[0] - 0.7).abs() < 0.02, "{}", reactor.rods[0]);
+}
@@ -0,0 +1,91 @@
+// The background sampler: its own cadence, the latest snapshot only, and a
+// quick shutdown
+
+use nuclear_monitor::metrics::MetricsSource;
+use nuclear_monitor::sampler::Sampler;
+use nuclear_monitor::system::SystemInfo;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Counts how often it is sampled, and reports the count as the CPU usage
+struct Counter(Arc<AtomicUsize>);
+
+impl MetricsSource for Counter {
+    fn sample(&mut self) -> Option<SystemInfo> {
+        let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Some(SystemInfo { cpu_usage: count as f32, ..SystemInfo::default() })
+    }
+}
+
+fn counted(interval: Duration) -> (Sampler, Arc<AtomicUsize>) {
+    let count = Arc::new(AtomicUsize::new(0));
+    (Sampler::spawn(Counter(count.clone()), interval), count)
+}
+
+// Poll like the render loop does until a snapshot turns up
+fn next_snapshot(sampler: &mut Sampler) -> SystemInfo {
+    let start = Instant::now();
+    loop {
+        if let Some(info) = sampler.sample() {
+            return info;
+        }
+        assert!(start.elapsed() < Duration::from_secs(5), "no snapshot arrived");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn samples_at_its_own_interval() {
+    let (sampler, count) = counted(Duration::from_millis(50));
+    thread::sleep(Duration::from_millis(500));
+    drop(sampler);
+
+    // About ten, however often the app asks; generous bounds for slow machines
+    let samples = count.load(Ordering::SeqCst);
+    assert!((3..=12).contains(&samples), "{} samples in 500 ms", samples);
+}
+
+#[test]
+fn hands_out_only_the_latest_snapshot_once() {
+    let (mut sampler, count) = counted(Duration::from_millis(10));
+    thread::sleep(Duration::from_millis(200));
+    let info = next_snapshot(&mut sampler);
+    // Everything queued up is skipped to the newest
+    assert!(info.cpu_usage >= 5.0, "got snapshot {}", info.cpu_usage);
+    assert!(info.cpu_usage <= count.load(Ordering::SeqCst) as f32);
+
+    let (mut sampler, _) = counted(Duration::from_secs(60));
+    assert_eq!(next_snapshot(&mut sampler).cpu_usage, 1.0);
+    assert_eq!(sampler.sample(), None);
+}
+
+#[test]
+fn dropping_stops_the_thread_without_waiting_out_the_interval() {
+    let (mut sampler, count) = counted(Duration::from_secs(60));
+    next_snapshot(&mut sampler);
+
+    let start = Instant::now();
+    drop(sampler);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn a_primed_sampler_publishes_a_delta_first() {
+    let (interval, start) = (Duration::from_millis(100), Instant::now());
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut sampler = Sampler::spawn_primed(Counter(count.clone()), interval);
+
+    // The baseline sample is never handed out
+    assert_eq!(next_snapshot(&mut sampler).cpu_usage, 2.0);
+    assert!(start.elapsed() >= interval);
+
+    // And it doesn't hold up shutdown either
+    let sampler = Sampler::spawn_primed(Counter(count), Duration::from_secs(60));
+    let stopping = Instant::now();
+    drop(sampler);
+    assert!(stopping.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,174 @@
+// Load-profile scenarios: segment shapes, playback, blending with the real
+// load, and the files they are read from
+
+use std::fs;
+use std::path::PathBuf;
+
+use nuclear_monitor::scenario::{self, Blend, Scenario, ScenarioPlayer, Segment};
+use nuclear_monitor::system::SystemInfo;
+
+fn scenario(repeat: bool, segments: Vec<Segment>) -> Scenario {
+    Scenario {
+        name: "Test".to_string(),
+        description: String::new(),
+        blend: Blend::Override,
+        repeat,
+        seed: 0,
+        segments,
+    }
+}
+
+// The level `t` seconds into a fresh playback
+fn level_at(segments: Vec<Segment>, t: f32) -> Option<f32> {
+    ScenarioPlayer::new(scenario(false, segments)).advance(t)
+}
+
+#[test]
+fn segments_have_their_shapes() {
+    let ramp = || vec![Segment::Ramp { from: 20.0, to: 60.0, duration: 8.0 }];
+    assert_eq!(level_at(ramp(), 0.0), Some(20.0));
+    assert_eq!(level_at(ramp(), 2.0), Some(30.0));
+
+    let sine = || vec![Segment::Sine { mean: 50.0, amplitude: 30.0, period: 8.0, duration: 16.0 }];
+    assert!((level_at(sine(), 2.0).unwrap() - 80.0).abs() < 1e-3);
+    assert!((level_at(sine(), 6.0).unwrap() - 20.0).abs() < 1e-3);
+
+    let square = || vec![Segment::Square { low: 10.0, high: 90.0, period: 4.0, duty: 0.25, duration: 8.0 }];
+    assert_eq!(level_at(square(), 0.5), Some(90.0));
+    assert_eq!(level_at(square(), 1.5), Some(10.0));
+    assert_eq!(level_at(square(), 4.5), Some(90.0));
+
+    let spikes = || vec![Segment::Spikes { base: 5.0, peak: 100.0, every: 10.0, width: 2.0, duration: 30.0 }];
+    assert_eq!(level_at(spikes(), 11.0), Some(100.0));
+    assert_eq!(level_at(spikes(), 15.0), Some(5.0));
+
+    let piecewise = || vec![Segment::Piecewise { points: vec![(0.0, 10.0), (4.0, 90.0), (8.0, 50.0)] }];
+    assert_eq!(level_at(piecewise(), 1.0), Some(30.0));
+    assert_eq!(level_at(piecewise(), 6.0), Some(70.0));
+
+    // Levels are load percentages, whatever the file says
+    assert_eq!(level_at(vec![Segment::Hold { level: 150.0, duration: 1.0 }], 0.5), Some(100.0));
+}
+
+#[test]
+fn segments_play_in_order_and_the_scenario_ends_or_repeats() {
+    let segments = || vec![
+        Segment::Hold { level: 10.0, duration: 2.0 },
+        Segment::Hold { level: 70.0, duration: 2.0 },
+    ];
+    let mut once = ScenarioPlayer::new(scenario(false, segments()));
+    assert_eq!(once.level, Some(10.0));
+    assert_eq!(once.advance(3.0), Some(70.0));
+    assert_eq!(once.advance(1.5), None);
+    assert!(once.is_finished());
+    assert_eq!(once.advance(1.0), None);
+
+    let mut repeating = ScenarioPlayer::new(scenario(true, segments()));
+    assert_eq!(repeating.advance(4.5), Some(10.0));
+    assert_eq!(repeating.elapsed, 0.5);
+    assert_eq!(repeating.advance(2.0), Some(70.0));
+}
+
+#[test]
+fn random_walks_stay_in_bounds_and_replay_the_same() {
+    let walk = || ScenarioPlayer::new(Scenario {
+        seed: 11,
+        ..scenario(false, vec![Segment::RandomWalk { start: 50.0, volatility: 40.0, min: 30.0, max: 60.0, duration: 60.0 }])
+    });
+    let levels = |mut player: ScenarioPlayer| -> Vec<f32> {
+        (0..600).map_while(|_| player.advance(0.1)).collect()
+    };
+    let first = levels(walk());
+    assert_eq!(first, levels(walk()));
+    assert!(first.iter().all(|level| (30.0..=60.0).contains(level)));
+    // It does wander, all the way to its limits
+    assert!(first.contains(&30.0) && first.contains(&60.0));
+}
+
+fn snapshot() -> SystemInfo {
+    SystemInfo {
+        cpu_usage: 40.0,
+        core_usage: vec![20.0, 60.0],
+        ..SystemInfo::default()
+    }
+}
+
+#[test]
+fn blends_combine_with_the_real_load() {
+    let mut player = ScenarioPlayer::new(scenario(false, vec![Segment::Hold { level: 50.0, duration: 10.0 }]));
+    let blended = |player: &ScenarioPlayer| {
+        let mut info = snapshot();
+        player.apply(&mut info);
+        (info.core_usage, info.cpu_usage)
+    };
+    assert_eq!(blended(&player), (vec![50.0, 50.0], 50.0));
+    player.blend = Blend::Add;
+    assert_eq!(blended(&player), (vec![70.0, 100.0], 85.0));
+    player.blend = Blend::Max;
+    assert_eq!(blended(&player), (vec![50.0, 60.0], 55.0));
+
+    // The machine's own load comes back once the scenario is over
+    player.advance(20.0);
+    assert_eq!(blended(&player), (vec![20.0, 60.0], 40.0));
+}
+
+#[test]
+fn the_bundled_scenarios_load() {
+    let (found, errors) = Scenario::load_dir(scenario::DEFAULT_SCENARIO_DIR);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(!found.is_empty());
+
+    let (library, _) = scenario::library(scenario::DEFAULT_SCENARIO_DIR);
+    assert_eq!(library.len(), Scenario::builtin().len() + found.len());
+    assert_eq!(library[0], Scenario::triangle());
+}
+
+fn scenario_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("nuclears-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn invalid_files_are_reported_and_skipped() {
+    let dir = scenario_dir("invalid");
+    let file = |name: &str, segments: &str, repeat: bool| {
+        let text = format!(r#"{{ "name": "{}", "repeat": {}, "segments": [{}] }}"#, name, repeat, segments);
+        fs::write(dir.join(format!("{}.json", name)), text).unwrap();
+    };
+    file("good", r#"{ "shape": "hold", "level": 40, "duration": 5 }"#, false);
+    file("empty", "", false);
+    file("no-period", r#"{ "shape": "sine", "mean": 50, "amplitude": 10, "period": 0, "duration": 5 }"#, false);
+    file("backwards", r#"{ "shape": "random_walk", "start": 50, "volatility": 5, "min": 80, "max": 20, "duration": 5 }"#, false);
+    file("unordered", r#"{ "shape": "piecewise", "points": [[5, 10], [2, 20]] }"#, false);
+    file("negative", r#"{ "shape": "ramp", "from": 0, "to": 10, "duration": -1 }"#, false);
+    file("endless", r#"{ "shape": "piecewise", "points": [[0, 10]] }"#, true);
+    file("unknown", r#"{ "shape": "sawtooth", "duration": 5 }"#, false);
+    fs::write(dir.join("notes.txt"), "not a scenario").unwrap();
+
+    let (found, errors) = Scenario::load_dir(&dir);
+    assert_eq!(found.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["good"]);
+    assert_eq!(errors.len(), 7, "{:#?}", errors);
+    assert!(errors.iter().all(|error| error.starts_with("Invalid scenario")));
+
+    // A missing directory is just an empty one
+    assert_eq!(Scenario::load_dir(dir.join("missing")), (Vec::new(), Vec::new()));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scenarios_are_found_by_name_or_path() {
+    let library = Scenario::builtin();
+    assert_eq!(scenario::find(" slow RAMP ", &library).unwrap().name, "Slow ramp");
+
+    let dir = scenario_dir("find");
+    let path = dir.join("mine.json");
+    fs::write(&path, r#"{ "name": "Mine", "blend": "add", "segments": [{ "shape": "hold", "level": 5, "duration": 1 }] }"#).unwrap();
+    let mine = scenario::find(path.to_str().unwrap(), &library).unwrap();
+    assert_eq!((mine.name.as_str(), mine.blend), ("Mine", Blend::Add));
+
+    let error = scenario::find("nope", &library).unwrap_err().to_string();
+    assert!(error.contains("nope") && error.contains("Triangle"), "{}", error);
+    fs::remove_dir_all(&dir).unwrap();
+}
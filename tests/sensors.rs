@@ -0,0 +1,46 @@
+// Temperature sensor selection, checked against sysfs fixtures under
+// tests/fixtures/sys*. A root other than /sys skips sysinfo, so these only
+// ever see the fixture files.
+
+use nuclear_monitor::sensors::{SensorReading, TemperatureProbe, TemperatureSensor};
+
+fn read(root: &str, sensor: TemperatureSensor) -> Option<SensorReading> {
+    let root = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), root);
+    TemperatureProbe::new(sensor, root).read()
+}
+
+fn reading(label: &str, celsius: f32) -> Option<SensorReading> {
+    Some(SensorReading { label: label.to_string(), celsius })
+}
+
+#[test]
+fn package_sensor_is_preferred() {
+    assert_eq!(read("sys", TemperatureSensor::Package), reading("Package id 0", 61.0));
+}
+
+#[test]
+fn core_and_hottest_sensors() {
+    assert_eq!(read("sys", TemperatureSensor::Core(1)), reading("Core 1", 64.0));
+    assert_eq!(read("sys", TemperatureSensor::Core(7)), None);
+    // Hottest means any sensor at all, the NVMe drive included
+    assert_eq!(read("sys", TemperatureSensor::Hottest), reading("Composite", 72.85));
+}
+
+#[test]
+fn package_falls_back_to_cpu_sensors_only() {
+    // The drive and the GPU are hotter, but aren't the CPU
+    assert_eq!(read("sys-no-package", TemperatureSensor::Package), reading("Core 3", 55.5));
+    assert_eq!(read("sys-no-cpu", TemperatureSensor::Package), None);
+    assert_eq!(read("sys-no-cpu", TemperatureSensor::Hottest), reading("Composite", 40.0));
+}
+
+#[test]
+fn thermal_zones_when_there_is_no_hwmon() {
+    assert_eq!(read("sys-thermal", TemperatureSensor::Package), reading("cpu-thermal", 47.2));
+    assert_eq!(read("sys-thermal", TemperatureSensor::Hottest), reading("cpu-thermal", 47.2));
+}
+
+#[test]
+fn missing_sysfs_reports_no_sensor() {
+    assert_eq!(read("nonexistent", TemperatureSensor::Package), None);
+}
@@ -0,0 +1,99 @@
+// Particle species: which metric emits each of them, and how they move and die
+
+use nuclear_monitor::reactor::{Particle, Reactor, ReactorInput, Species, FIXED_TIMESTEP};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn load(cpu_load: f32) -> ReactorInput {
+    ReactorInput {
+        cpu_load,
+        core_loads: vec![cpu_load; 4],
+        ..ReactorInput::default()
+    }
+}
+
+// The most of each species seen alive at once over `seconds`
+fn peak_counts(input: &ReactorInput, seconds: f32) -> [usize; 4] {
+    let mut reactor = Reactor::with_seed(3);
+    let mut peak = [0; 4];
+    for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+        reactor.step(input);
+        for (peak, count) in peak.iter_mut().zip(reactor.species_counts()) {
+            *peak = (*peak).max(count);
+        }
+    }
+    peak
+}
+
+#[test]
+fn each_metric_emits_its_own_species() {
+    let [alpha, beta, gamma, neutron] = peak_counts(&load(30.0), 10.0);
+    assert_eq!((alpha, beta, gamma), (0, 0, 0));
+    assert!(neutron > 0);
+
+    let disk = peak_counts(&ReactorInput { disk_io_rate: 100.0e6, ..load(30.0) }, 10.0);
+    assert!(disk[Species::Alpha as usize] > 0 && disk[1..3] == [0, 0], "{:?}", disk);
+
+    let switches = peak_counts(&ReactorInput { context_switch_rate: 100.0e3, ..load(30.0) }, 10.0);
+    assert!(switches[Species::Beta as usize] > 0 && switches[0] == 0 && switches[2] == 0, "{:?}", switches);
+
+    let interrupts = peak_counts(&ReactorInput { interrupt_rate: 50.0e3, ..load(30.0) }, 10.0);
+    assert!(interrupts[Species::Beta as usize] > 0, "{:?}", interrupts);
+
+    let faults = peak_counts(&ReactorInput { page_fault_rate: 50.0e3, ..load(30.0) }, 10.0);
+    assert!(faults[Species::Gamma as usize] > 0 && faults[..2] == [0, 0], "{:?}", faults);
+}
+
+#[test]
+fn gammas_leave_the_core_and_alphas_are_absorbed() {
+    let quiet = load(30.0);
+    let busy = ReactorInput {
+        disk_io_rate: 100.0e6,
+        context_switch_rate: 100.0e3,
+        page_fault_rate: 50.0e3,
+        ..quiet.clone()
+    };
+    let mut reactor = Reactor::with_seed(7);
+    for _ in 0..(5.0 / FIXED_TIMESTEP) as usize {
+        reactor.step(&busy);
+    }
+    let [alpha, beta, gamma, _] = reactor.species_counts();
+    assert!(alpha > 0 && beta > 0 && gamma > 0);
+
+    // Gammas live for seconds, but pass through the casing and off the screen
+    // well before that
+    for _ in 0..(1.5 / FIXED_TIMESTEP) as usize {
+        reactor.step(&quiet);
+    }
+    assert_eq!(reactor.species_counts()[Species::Gamma as usize], 0);
+
+    // Alphas are soon gone, while betas are still bouncing around the core
+    for _ in 0..(1.0 / FIXED_TIMESTEP) as usize {
+        reactor.step(&quiet);
+    }
+    let [alpha, beta, _, _] = reactor.species_counts();
+    assert_eq!(alpha, 0);
+    assert!(beta > 0);
+    for particle in &reactor.particles {
+        if particle.species != Species::Gamma {
+            assert!((0.0..=1.0).contains(&particle.x) && (0.0..=1.0).contains(&particle.y), "{:?} escaped", particle.species);
+        }
+    }
+}
+
+#[test]
+fn species_differ_in_mass() {
+    let energy = |species| Particle::of_species(species, 0.5, 0.5, 0.5, &mut StdRng::seed_from_u64(0)).energy;
+    let [alpha, beta, gamma, neutron] = Species::ALL.map(energy);
+    assert!(alpha > neutron && neutron > beta && beta > gamma, "{} {} {} {}", alpha, neutron, beta, gamma);
+    assert_eq!(Particle::new(0.5, 0.5, 0.5, &mut StdRng::seed_from_u64(0)).species, Species::Neutron);
+}
+
+#[test]
+fn neutrons_split_the_fuel() {
+    let mut reactor = Reactor::with_seed(3);
+    for _ in 0..(10.0 / FIXED_TIMESTEP) as usize {
+        reactor.step(&load(30.0));
+    }
+    assert!(reactor.fuel.fissions > 0);
+}
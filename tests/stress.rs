@@ -0,0 +1,118 @@
+// The stress test: workers that really load the machine, at the utilization
+// asked for, and that all stop when told to. Worker threads are found by
+// name under /proc, so the tests here take turns.
+
+use std::fs;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nuclear_monitor::stress::{StressConfig, StressTest};
+
+static ONE_AT_A_TIME: Mutex<()> = Mutex::new(());
+
+// Clock ticks per second of the times in /proc/<pid>/stat; 100 on every
+// Linux configuration worth testing on
+const TICKS_PER_SECOND: f32 = 100.0;
+
+// CPU seconds used so far by each running stress worker
+fn worker_cpu_times() -> Vec<f32> {
+    let Ok(tasks) = fs::read_dir("/proc/self/task") else {
+        return Vec::new();
+    };
+    // The main thread is named after the test binary, stress-<hash>
+    let main_thread = std::process::id().to_string();
+    tasks
+        .filter_map(|task| task.ok())
+        .filter(|task| task.file_name() != main_thread.as_str())
+        .filter_map(|task| fs::read_to_string(task.path().join("stat")).ok())
+        .filter(|stat| stat.contains(" (stress-"))
+        .filter_map(|stat| {
+            let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+            let user: f32 = fields.get(11)?.parse().ok()?;
+            let system: f32 = fields.get(12)?.parse().ok()?;
+            Some((user + system) / TICKS_PER_SECOND)
+        })
+        .collect()
+}
+
+// Fraction of `seconds` that one worker spent on the CPU, on average
+fn busy_fraction(config: StressConfig, seconds: f32) -> f32 {
+    let stress = StressTest::start(config);
+    thread::sleep(Duration::from_millis(200));
+    let before: f32 = worker_cpu_times().iter().sum();
+    thread::sleep(Duration::from_secs_f32(seconds));
+    let after: f32 = worker_cpu_times().iter().sum();
+    let workers = stress.worker_count() as f32;
+    stress.stop();
+    (after - before) / seconds / workers
+}
+
+#[test]
+fn workers_hold_the_target_utilization() {
+    let _turn = ONE_AT_A_TIME.lock().unwrap_or_else(|e| e.into_inner());
+    if fs::metadata("/proc/self/task").is_err() {
+        return;
+    }
+    let flat_out = busy_fraction(StressConfig { workers: 1, ..StressConfig::default() }, 1.0);
+    assert!(flat_out > 0.8, "busy {:.0}% of the time at 100%", flat_out * 100.0);
+
+    let third = busy_fraction(StressConfig { workers: 1, utilization: 30.0, ..StressConfig::default() }, 2.0);
+    assert!((0.15..0.45).contains(&third), "busy {:.0}% of the time at 30%", third * 100.0);
+}
+
+#[test]
+fn every_core_gets_a_worker_by_default() {
+    let _turn = ONE_AT_A_TIME.lock().unwrap_or_else(|e| e.into_inner());
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    let stress = StressTest::start(StressConfig {
+        utilization: 0.0,
+        memory_workers: 1,
+        alloc_workers: 2,
+        memory_mb: 1,
+        ..StressConfig::default()
+    });
+    assert_eq!(stress.cpu_workers, cores);
+    assert_eq!(stress.worker_count(), cores + 3);
+    assert!(!stress.is_expired());
+    stress.stop();
+}
+
+#[test]
+fn stopping_joins_every_worker() {
+    let _turn = ONE_AT_A_TIME.lock().unwrap_or_else(|e| e.into_inner());
+    let config = StressConfig {
+        workers: 2,
+        memory_workers: 1,
+        alloc_workers: 1,
+        memory_mb: 16,
+        ..StressConfig::default()
+    };
+    let stress = StressTest::start(config);
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(worker_cpu_times().len(), 4);
+
+    // Workers notice between chunks of work, not at the end of a duty period
+    let stopping = Instant::now();
+    stress.stop();
+    assert!(stopping.elapsed() < Duration::from_millis(500), "took {:?} to stop", stopping.elapsed());
+    assert!(worker_cpu_times().is_empty());
+
+    // Dropping it does the same
+    drop(StressTest::start(config));
+    assert!(worker_cpu_times().is_empty());
+}
+
+#[test]
+fn the_duration_limit_stops_the_workers() {
+    let _turn = ONE_AT_A_TIME.lock().unwrap_or_else(|e| e.into_inner());
+    let stress = StressTest::start(StressConfig {
+        workers: 2,
+        duration: Some(Duration::from_millis(300)),
+        ..StressConfig::default()
+    });
+    thread::sleep(Duration::from_millis(800));
+    assert!(stress.is_expired());
+    assert!(worker_cpu_times().is_empty(), "workers still running past the limit");
+    stress.stop();
+}